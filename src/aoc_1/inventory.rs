@@ -0,0 +1,281 @@
+use std::{collections::BTreeMap, fmt::Display};
+
+use crate::top_k::TopK;
+
+type Calories = u64;
+
+/// One elf and the calories of every item it carries, numbered from 1 in input order
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Elf {
+    pub number: usize,
+    pub items: Vec<Calories>,
+}
+
+impl Elf {
+    pub fn total(&self) -> Calories {
+        self.items.iter().sum()
+    }
+
+    pub fn item_count(&self) -> usize {
+        self.items.len()
+    }
+}
+
+/// Every elf of the input, kept whole so we can ask questions about the distribution
+#[derive(Debug, Default)]
+pub struct Inventory {
+    elves: Vec<Elf>,
+}
+
+impl Inventory {
+    /// Each elf is a block of lines separated by a blank line
+    pub fn parse<I: Iterator<Item = String>>(lines: I) -> Self {
+        let mut elves = vec![];
+        let mut current: Option<Vec<Calories>> = None;
+
+        for line in lines {
+            if line.is_empty() {
+                elves.push(current.take().unwrap_or_default());
+            } else {
+                let calories = line
+                    .parse::<Calories>()
+                    .unwrap_or_else(|_| panic!("Expect a number get {}", line));
+                current.get_or_insert_with(Vec::new).push(calories);
+            }
+        }
+
+        if let Some(last) = current {
+            elves.push(last);
+        }
+
+        Inventory {
+            elves: elves
+                .into_iter()
+                .enumerate()
+                .map(|(i, items)| Elf {
+                    number: i + 1,
+                    items,
+                })
+                .collect(),
+        }
+    }
+
+    pub fn elves(&self) -> &[Elf] {
+        &self.elves
+    }
+
+    pub fn item_count(&self) -> usize {
+        self.elves.iter().map(|e| e.item_count()).sum()
+    }
+
+    pub fn total(&self) -> Calories {
+        self.elves.iter().map(|e| e.total()).sum()
+    }
+
+    /// The `n` elves carrying the most calories, most first
+    pub fn top(&self, n: usize) -> Vec<&Elf> {
+        let mut top = TopK::new(n);
+        for (i, elf) in self.elves.iter().enumerate() {
+            top.add_with(elf.total(), i);
+        }
+
+        top.into_sorted_vec()
+            .into_iter()
+            .map(|(_, i)| &self.elves[i])
+            .collect()
+    }
+
+    fn sorted_totals(&self) -> Vec<Calories> {
+        let mut totals: Vec<Calories> = self.elves.iter().map(|e| e.total()).collect();
+        totals.sort();
+        totals
+    }
+
+    pub fn mean(&self) -> Option<f64> {
+        if self.elves.is_empty() {
+            None
+        } else {
+            Some(self.total() as f64 / self.elves.len() as f64)
+        }
+    }
+
+    pub fn median(&self) -> Option<f64> {
+        let totals = self.sorted_totals();
+        let n = totals.len();
+
+        match n {
+            0 => None,
+            n if n % 2 == 1 => Some(totals[n / 2] as f64),
+            n => Some((totals[n / 2 - 1] + totals[n / 2]) as f64 / 2.0),
+        }
+    }
+
+    /// Nearest-rank percentile of the elf totals, `p` between 0 and 100
+    pub fn percentile(&self, p: f64) -> Option<Calories> {
+        assert!((0.0..=100.0).contains(&p), "Percentile out of range {}", p);
+
+        let totals = self.sorted_totals();
+        if totals.is_empty() {
+            return None;
+        }
+
+        let rank = ((p / 100.0) * totals.len() as f64).ceil() as usize;
+        Some(totals[rank.clamp(1, totals.len()) - 1])
+    }
+
+    /// Totals carried by more than one elf, largest total first
+    pub fn ties(&self) -> Vec<(Calories, Vec<usize>)> {
+        let mut by_total: BTreeMap<Calories, Vec<usize>> = BTreeMap::new();
+        for elf in self.elves.iter() {
+            by_total.entry(elf.total()).or_default().push(elf.number);
+        }
+
+        by_total
+            .into_iter()
+            .rev()
+            .filter(|(_, numbers)| numbers.len() > 1)
+            .collect()
+    }
+}
+
+/// Report printed by `aoc stats 1`
+pub struct Stats<'a> {
+    pub inventory: &'a Inventory,
+    pub top: usize,
+}
+
+impl Display for Stats<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let inventory = self.inventory;
+        let format_f64 = |v: Option<f64>| v.map_or("-".to_string(), |v| format!("{:.1}", v));
+        let format_u64 = |v: Option<Calories>| v.map_or("-".to_string(), |v| v.to_string());
+
+        writeln!(f, "Elves: {}", inventory.elves().len())?;
+        writeln!(f, "Items: {}", inventory.item_count())?;
+        writeln!(f, "Total calories: {}", inventory.total())?;
+        writeln!(f, "Mean: {}", format_f64(inventory.mean()))?;
+        writeln!(f, "Median: {}", format_f64(inventory.median()))?;
+        for p in [25.0, 75.0, 90.0, 99.0] {
+            writeln!(f, "P{}: {}", p, format_u64(inventory.percentile(p)))?;
+        }
+
+        writeln!(f, "Top {}:", self.top)?;
+        for elf in inventory.top(self.top) {
+            writeln!(
+                f,
+                "  elf #{}: {} calories in {} items",
+                elf.number,
+                elf.total(),
+                elf.item_count()
+            )?;
+        }
+
+        let ties = inventory.ties();
+        if ties.is_empty() {
+            writeln!(f, "Ties: none")
+        } else {
+            writeln!(f, "Ties:")?;
+            for (total, numbers) in ties {
+                let numbers: Vec<String> = numbers.iter().map(|n| format!("#{}", n)).collect();
+                writeln!(f, "  {} calories: elves {}", total, numbers.join(", "))?;
+            }
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: [&str; 14] = [
+        "1000", "2000", "3000", "", "4000", "", "5000", "6000", "", "7000", "8000", "9000", "",
+        "10000",
+    ];
+
+    fn inventory() -> Inventory {
+        Inventory::parse(INPUT.iter().map(|s| s.to_string()))
+    }
+
+    #[test]
+    fn test_parse() {
+        let inventory = inventory();
+
+        assert_eq!(5, inventory.elves().len());
+        assert_eq!(10, inventory.item_count());
+        assert_eq!(
+            Elf {
+                number: 3,
+                items: vec![5000, 6000]
+            },
+            inventory.elves()[2]
+        );
+        assert_eq!(55000, inventory.total());
+    }
+
+    #[test]
+    fn test_top() {
+        let inventory = inventory();
+
+        itertools::assert_equal(inventory.top(3).iter().map(|e| e.number), vec![4, 3, 5]);
+        itertools::assert_equal(
+            inventory.top(3).iter().map(|e| e.total()),
+            vec![24000, 11000, 10000],
+        );
+    }
+
+    #[test]
+    fn test_distribution() {
+        let inventory = inventory();
+
+        assert_eq!(Some(11000.0), inventory.mean());
+        assert_eq!(Some(10000.0), inventory.median());
+        assert_eq!(Some(4000), inventory.percentile(0.0));
+        assert_eq!(Some(6000), inventory.percentile(25.0));
+        assert_eq!(Some(24000), inventory.percentile(100.0));
+
+        let even = Inventory::parse(["1", "", "4"].iter().map(|s| s.to_string()));
+        assert_eq!(Some(2.5), even.median());
+
+        let empty = Inventory::parse(std::iter::empty());
+        assert_eq!(None, empty.mean());
+        assert_eq!(None, empty.percentile(50.0));
+    }
+
+    #[test]
+    fn test_ties() {
+        let input = ["3", "", "1", "2", "", "5", "", "2", "1", "", "5"];
+        let inventory = Inventory::parse(input.iter().map(|s| s.to_string()));
+
+        assert_eq!(vec![(5, vec![3, 5]), (3, vec![1, 2, 4])], inventory.ties());
+    }
+
+    #[test]
+    fn test_report() {
+        let inventory = inventory();
+        let report = Stats {
+            inventory: &inventory,
+            top: 2,
+        }
+        .to_string();
+
+        let expected = [
+            "Elves: 5",
+            "Items: 10",
+            "Total calories: 55000",
+            "Mean: 11000.0",
+            "Median: 10000.0",
+            "P25: 6000",
+            "P75: 11000",
+            "P90: 24000",
+            "P99: 24000",
+            "Top 2:",
+            "  elf #4: 24000 calories in 3 items",
+            "  elf #3: 11000 calories in 2 items",
+            "Ties: none\n",
+        ]
+        .join("\n");
+
+        assert_eq!(expected, report);
+    }
+}
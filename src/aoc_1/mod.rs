@@ -1,108 +1,116 @@
-use std::{fmt::Display, iter::Sum};
+pub mod inventory;
 
-use crate::get_input;
+use crate::{
+    runner::{Lines, Solution},
+    stream_input,
+    top_k::TopK,
+};
 
-pub fn aoc_1_1() {
-    let input = get_input("resource/aoc_1/data.txt");
-
-    let mut max: u64 = 0;
-    let mut current: u64 = 0;
-    for line in input.iter() {
-        if line == "" {
-            if current > max {
-                max = current;
+pub(crate) struct Part1;
+pub(crate) struct Part2;
+
+/// Total calories carried by each elf, in input order, computed lazily from the lines
+pub(crate) struct ElfTotals<I: Iterator<Item = String>> {
+    lines: I,
+}
+
+impl<I: Iterator<Item = String>> ElfTotals<I> {
+    fn new(lines: I) -> Self {
+        ElfTotals { lines }
+    }
+}
+
+impl<I: Iterator<Item = String>> Iterator for ElfTotals<I> {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut current: Option<u64> = None;
+
+        for line in self.lines.by_ref() {
+            if line.is_empty() {
+                return Some(current.unwrap_or(0));
             }
-            current = 0
-        } else {
-            current += line
+
+            let calories = line
                 .parse::<u64>()
-                .expect(&format!("Expect a number get {}", &line));
+                .unwrap_or_else(|_| panic!("Expect a number get {}", line));
+            current = Some(current.unwrap_or(0) + calories);
         }
-    }
 
-    println!("AOC-1-1 Max 1 elf: {}", &max);
+        current //last elf when the input does not end with a blank line
+    }
 }
 
-pub fn aoc_1_2() {
-    let input = get_input("resource/aoc_1/data.txt");
+impl Solution for Part1 {
+    type Parsed = ElfTotals<Lines>;
+    type Output = u64;
 
-    let mut top3 = OrderedList::new();
+    const STREAMING: bool = true;
 
-    let mut current: u64 = 0;
-    for line in input.iter() {
-        if line == "" {
-            top3.add(current);
-            current = 0
-        } else {
-            current += line
-                .parse::<u64>()
-                .expect(&format!("Expect a number get {}", &line));
-        }
+    fn parse(&self, input: Vec<String>) -> Self::Parsed {
+        self.parse_lines(Box::new(input.into_iter()))
     }
 
-    println!("AOC-1-2 Max 3 elf: {}", &top3.sum());
-}
+    fn parse_lines(&self, lines: Lines) -> Self::Parsed {
+        ElfTotals::new(lines)
+    }
 
-pub struct OrderedList<T>
-where
-    T: Ord + Sum + Copy + Display,
-{
-    internal_list: Vec<T>,
+    fn solve(&self, parsed: Self::Parsed) -> Self::Output {
+        parsed.max().unwrap_or(0)
+    }
 }
 
-impl<T> OrderedList<T>
-where
-    T: Ord + Sum + Copy + Display,
-{
-    pub fn new() -> Self {
-        OrderedList {
-            internal_list: vec![],
-        }
-    }
+impl Solution for Part2 {
+    type Parsed = ElfTotals<Lines>;
+    type Output = u64;
 
-    pub fn add(&mut self, e: T) {
-        if self.internal_list.is_empty() {
-            self.internal_list.push(e)
-        } else {
-            let mut i = self.internal_list.len();
-            while i > 0 && &e > &self.internal_list[i - 1] {
-                i -= 1;
-            }
+    const STREAMING: bool = true;
 
-            self.internal_list.insert(i, e);
+    fn parse(&self, input: Vec<String>) -> Self::Parsed {
+        self.parse_lines(Box::new(input.into_iter()))
+    }
 
-            if self.internal_list.len() > 3 {
-                self.internal_list.pop();
-            }
-        }
+    fn parse_lines(&self, lines: Lines) -> Self::Parsed {
+        ElfTotals::new(lines)
     }
 
-    pub fn sum(&self) -> T {
-        self.internal_list.iter().copied().sum()
+    fn solve(&self, parsed: Self::Parsed) -> Self::Output {
+        let mut top3 = TopK::new(3);
+        top3.extend(parsed);
+        top3.sum()
     }
 }
 
+pub fn aoc_1_1() {
+    let input = stream_input("resource/aoc_1/data.txt").expect("Cannot read file");
+
+    let max = Part1.solve(Part1.parse_lines(Box::new(input)));
+
+    println!("AOC-1-1 Max 1 elf: {}", &max);
+}
+
+pub fn aoc_1_2() {
+    let input = stream_input("resource/aoc_1/data.txt").expect("Cannot read file");
+
+    let top3 = Part2.solve(Part2.parse_lines(Box::new(input)));
+
+    println!("AOC-1-2 Max 3 elf: {}", &top3);
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
-    fn test_ordered_list() {
-        let mut list = OrderedList::new();
-
-        assert_eq!(0, list.sum());
-
-        list.add(3);
-        list.add(2);
-        list.add(1);
-
-        assert_eq!(6, list.sum());
+    fn test_elf_totals() {
+        let input = ["1000", "2000", "", "4000", "", "", "5000", "6000"];
 
-        assert_eq!(1, *list.internal_list.last().unwrap());
+        let totals = ElfTotals::new(input.iter().map(|s| s.to_string()));
+        itertools::assert_equal(totals, vec![3000, 4000, 0, 11000]);
 
-        list.add(4);
-        assert_eq!(3, list.internal_list.len());
-        assert_eq!(9, list.sum());
+        let with_trailing_blank = ["1000", ""];
+        let totals = ElfTotals::new(with_trailing_blank.iter().map(|s| s.to_string()));
+        itertools::assert_equal(totals, vec![1000]);
     }
 
     #[test]
@@ -1,11 +1,14 @@
-use std::{collections::VecDeque, fmt::Display};
+use std::fmt::Display;
 
 use lazy_static::lazy_static;
 use regex::Regex;
 
-use crate::get_input;
+use crate::{
+    runner::{Lines, Solution},
+    stream_input,
+};
 
-enum Instruction {
+pub(crate) enum Instruction {
     Noop,
     Add(i32),
 }
@@ -19,13 +22,20 @@ impl Instruction {
     }
 }
 
-struct Stack {
-    stack: VecDeque<Instruction>,
+/// Instructions expanded to one per cycle, read lazily from the program
+pub(crate) struct Stack {
+    stack: Box<dyn Iterator<Item = Instruction>>,
 }
 
 impl Stack {
+    fn from_lines(lines: Lines) -> Self {
+        Stack {
+            stack: Box::new(lines.flat_map(|s| to_instructions(&s))),
+        }
+    }
+
     fn update_register(&mut self, register: i32) -> i32 {
-        let instr = self.stack.pop_front();
+        let instr = self.stack.next();
         match instr {
             Some(instr) => instr.update_register(register),
             None => panic!("No more instructions"),
@@ -42,35 +52,38 @@ impl Stack {
     }
 }
 
+/// An addx takes 2 cycles, so it is preceded by a noop
+fn to_instructions(s: &str) -> Vec<Instruction> {
+    lazy_static! {
+        static ref NOOP_REGEX: Regex = Regex::new("noop").unwrap();
+        static ref ADDX_REGEX: Regex = Regex::new("addx (?P<x>[-]?[0-9]+)").unwrap();
+    }
+
+    if NOOP_REGEX.is_match(s) {
+        vec![Instruction::Noop]
+    } else {
+        let captures = ADDX_REGEX.captures(s).unwrap();
+        let to_add = captures["x"].parse::<i32>().unwrap();
+        vec![Instruction::Noop, Instruction::Add(to_add)]
+    }
+}
+
 impl<'a> FromIterator<&'a str> for Stack {
     fn from_iter<T: IntoIterator<Item = &'a str>>(iter: T) -> Self {
-        lazy_static! {
-            static ref NOOP_REGEX: Regex = Regex::new("noop").unwrap();
-            static ref ADDX_REGEX: Regex = Regex::new("addx (?P<x>[-]?[0-9]+)").unwrap();
-        }
+        let stack: Vec<Instruction> = iter.into_iter().flat_map(to_instructions).collect();
 
-        let mut stack = VecDeque::new();
-        for s in iter {
-            if NOOP_REGEX.is_match(s) {
-                stack.push_back(Instruction::Noop);
-            } else {
-                let captures = ADDX_REGEX.captures(s).unwrap();
-                let to_add = captures["x"].parse::<i32>().unwrap();
-                stack.push_back(Instruction::Noop);
-                stack.push_back(Instruction::Add(to_add));
-            }
+        Stack {
+            stack: Box::new(stack.into_iter()),
         }
-
-        Stack { stack }
     }
 }
 
 impl IntoIterator for Stack {
     type Item = Instruction;
-    type IntoIter = std::collections::vec_deque::IntoIter<Self::Item>;
+    type IntoIter = Box<dyn Iterator<Item = Instruction>>;
 
     fn into_iter(self) -> Self::IntoIter {
-        self.stack.into_iter()
+        self.stack
     }
 }
 
@@ -88,7 +101,7 @@ impl Display for ScreenState {
     }
 }
 
-struct Screen {
+pub(crate) struct Screen {
     states: Vec<ScreenState>,
 }
 
@@ -132,30 +145,69 @@ fn run_instructions(stack: Stack) -> Screen {
     screen
 }
 
-pub fn aoc_10_1() {
-    let input = get_input("resource/aoc_10/data.txt");
+pub(crate) struct Part1;
+pub(crate) struct Part2;
 
-    let mut stack: Stack = input.iter().map(|s| s.as_str()).into_iter().collect();
+impl Solution for Part1 {
+    type Parsed = Stack;
+    type Output = i32;
 
-    let mut register = 1;
+    const STREAMING: bool = true;
 
-    register = stack.after_n_instructions(register, 19);
-    let mut signal = 20 * register;
+    fn parse(&self, input: Vec<String>) -> Self::Parsed {
+        input.iter().map(|s| s.as_str()).collect()
+    }
 
-    for i in 1..=5 {
-        register = stack.after_n_instructions(register, 40);
-        signal += register * (20 + 40 * i);
+    fn parse_lines(&self, lines: Lines) -> Self::Parsed {
+        Stack::from_lines(lines)
+    }
+
+    fn solve(&self, mut stack: Self::Parsed) -> Self::Output {
+        let mut register = 1;
+
+        register = stack.after_n_instructions(register, 19);
+        let mut signal = 20 * register;
+
+        for i in 1..=5 {
+            register = stack.after_n_instructions(register, 40);
+            signal += register * (20 + 40 * i);
+        }
+
+        signal
     }
+}
+
+impl Solution for Part2 {
+    type Parsed = Stack;
+    type Output = Screen;
+
+    const STREAMING: bool = true;
+
+    fn parse(&self, input: Vec<String>) -> Self::Parsed {
+        input.iter().map(|s| s.as_str()).collect()
+    }
+
+    fn parse_lines(&self, lines: Lines) -> Self::Parsed {
+        Stack::from_lines(lines)
+    }
+
+    fn solve(&self, stack: Self::Parsed) -> Self::Output {
+        run_instructions(stack)
+    }
+}
+
+pub fn aoc_10_1() {
+    let input = stream_input("resource/aoc_10/data.txt").expect("Cannot read file");
+
+    let signal = Part1.solve(Part1.parse_lines(Box::new(input)));
 
     println!("AOC-10-1 signal {}", signal);
 }
 
 pub fn aoc_10_2() {
-    let input = get_input("resource/aoc_10/data.txt");
+    let input = stream_input("resource/aoc_10/data.txt").expect("Cannot read file");
 
-    let stack: Stack = input.iter().map(|s| s.as_str()).into_iter().collect();
-
-    let screen = run_instructions(stack);
+    let screen = Part2.solve(Part2.parse_lines(Box::new(input)));
 
     println!("AOC-10-2 screen\n{}", screen);
 }
@@ -212,6 +264,19 @@ mod tests {
         assert_eq!(register, 18);
     }
 
+    #[test]
+    fn test_stream_aftern() {
+        let input = stream_input("resource/aoc_10/test_data.txt").unwrap();
+
+        let mut stack = Stack::from_lines(Box::new(input));
+
+        let mut register = 1;
+        register = stack.after_n_instructions(register, 19);
+        assert_eq!(register, 21);
+        register = stack.after_n_instructions(register, 40);
+        assert_eq!(register, 19);
+    }
+
     #[test]
     fn test_aoc_10_1() {
         aoc_10_1()
@@ -3,12 +3,12 @@ use std::collections::VecDeque;
 use lazy_static::lazy_static;
 use regex::Regex;
 
-use crate::get_input;
+use crate::{get_input, runner::Solution};
 
 type WorryLevel = u64;
 type MonkeyNb = usize;
 type UpdateFunction = Box<dyn Fn(WorryLevel) -> WorryLevel>;
-struct Monkey {
+pub(crate) struct Monkey {
     nb: MonkeyNb,
     worry_lvs: VecDeque<WorryLevel>,
     update_fn: UpdateFunction,
@@ -72,21 +72,48 @@ fn play_20_rounds(monkeys: &mut Vec<Monkey>) -> u64 {
     play_n_rounds(monkeys, 20)
 }
 
+pub(crate) struct Part1;
+pub(crate) struct Part2;
+
+impl Solution for Part1 {
+    type Parsed = Vec<Monkey>;
+    type Output = u64;
+
+    fn parse(&self, input: Vec<String>) -> Self::Parsed {
+        parse(input.into_iter())
+    }
+
+    fn solve(&self, mut monkeys: Self::Parsed) -> Self::Output {
+        play_20_rounds(&mut monkeys)
+    }
+}
+
+impl Solution for Part2 {
+    type Parsed = Vec<Monkey>;
+    type Output = u64;
+
+    fn parse(&self, input: Vec<String>) -> Self::Parsed {
+        parse(input.into_iter())
+    }
+
+    fn solve(&self, mut monkeys: Self::Parsed) -> Self::Output {
+        update_dampen_func(&mut monkeys);
+        play_n_rounds(&mut monkeys, 10000)
+    }
+}
+
 pub fn aoc_11_1() {
     let input = get_input("resource/aoc_11/data.txt");
-    let mut monkeys = parse(input.into_iter());
 
-    let monkey_biz = play_20_rounds(&mut monkeys);
+    let monkey_biz = Part1.solve(Part1.parse(input));
 
     println!("AOC-11-1 monkey biz {}", monkey_biz);
 }
 
 pub fn aoc_11_2() {
     let input = get_input("resource/aoc_11/data.txt");
-    let mut monkeys = parse(input.into_iter());
 
-    update_dampen_func(&mut monkeys);
-    let monkey_biz = play_n_rounds(&mut monkeys, 10000);
+    let monkey_biz = Part2.solve(Part2.parse(input));
 
     println!("AOC-11-2 monkey biz 10000 {}", monkey_biz);
 }
@@ -5,12 +5,12 @@ use std::{
     rc::Rc,
 };
 
-use crate::get_input;
+use crate::{get_input, runner::Solution};
 
 type Grid = Vec<Vec<Square>>;
 type NodeRef = Rc<RefCell<GraphNode>>;
 #[derive(Clone, Copy, PartialEq, Eq, Debug, Hash, PartialOrd)]
-struct Position {
+pub(crate) struct Position {
     row: usize,
     col: usize,
 }
@@ -50,7 +50,7 @@ impl Square {
 }
 
 #[derive(PartialEq, Eq, Debug)]
-struct GraphNode {
+pub(crate) struct GraphNode {
     visited: bool,
     tentative_dist: usize,
     position: Position,
@@ -316,27 +316,53 @@ fn min_route<F: Fn(&Ref<GraphNode>) -> bool>(
     unreachable!()
 }
 
-pub fn aoc_12_1() {
-    let input = get_input("resource/aoc_12/data.txt");
+pub(crate) struct Part1;
+pub(crate) struct Part2;
 
-    let mut nodes = parse_up(input.into_iter());
+impl Solution for Part1 {
+    type Parsed = HashMap<Position, NodeRef>;
+    type Output = usize;
 
-    prep_min_route(&mut nodes, |n| n.is_start);
+    fn parse(&self, input: Vec<String>) -> Self::Parsed {
+        parse_up(input.into_iter())
+    }
 
-    let min = min_route(&nodes, |n| n.is_goal);
+    fn solve(&self, mut nodes: Self::Parsed) -> Self::Output {
+        prep_min_route(&mut nodes, |n| n.is_start);
 
-    println!("AOC-12-1 shortest route {}", min.unwrap());
+        min_route(&nodes, |n| n.is_goal).unwrap()
+    }
 }
 
-pub fn aoc_12_2() {
+impl Solution for Part2 {
+    type Parsed = HashMap<Position, NodeRef>;
+    type Output = usize;
+
+    fn parse(&self, input: Vec<String>) -> Self::Parsed {
+        parse_down(input.into_iter())
+    }
+
+    fn solve(&self, mut nodes: Self::Parsed) -> Self::Output {
+        prep_min_route(&mut nodes, |n| n.is_goal);
+
+        min_route(&nodes, |n| n.elevation == 'a').unwrap()
+    }
+}
+
+pub fn aoc_12_1() {
     let input = get_input("resource/aoc_12/data.txt");
 
-    let mut nodes = parse_down(input.into_iter());
+    let min = Part1.solve(Part1.parse(input));
+
+    println!("AOC-12-1 shortest route {}", min);
+}
+
+pub fn aoc_12_2() {
+    let input = get_input("resource/aoc_12/data.txt");
 
-    prep_min_route(&mut nodes, |n| n.is_goal);
-    let min = min_route(&nodes, |n| n.elevation == 'a');
+    let min = Part2.solve(Part2.parse(input));
 
-    println!("AOC-12-2 shortest among low elevation {}", min.unwrap());
+    println!("AOC-12-2 shortest among low elevation {}", min);
 }
 
 #[cfg(test)]
@@ -2,10 +2,10 @@ use std::{fmt::Display, iter::Peekable};
 
 use itertools::Itertools;
 
-use crate::get_input;
+use crate::{get_input, runner::Solution};
 
 #[derive(PartialEq, Eq, Debug, Ord)]
-enum PacketData {
+pub(crate) enum PacketData {
     Int(u32),
     Vec(Vec<Box<PacketData>>),
 }
@@ -134,15 +134,6 @@ fn sum_ok_pairs(pairs: Vec<(PacketData, PacketData)>) -> usize {
         .sum()
 }
 
-pub fn aoc_13_1() {
-    let input = get_input("resource/aoc_13/data.txt");
-    let packets = parse(input.into_iter());
-
-    let sum = sum_ok_pairs(packets);
-
-    println!("AOC-13-1 sum ok pairs: {}", &sum)
-}
-
 fn sort_packets(packets: Vec<(PacketData, PacketData)>) -> Vec<PacketData> {
     let mut all_packets: Vec<PacketData> = packets
         .into_iter()
@@ -172,11 +163,47 @@ fn find_dividers(packets: Vec<PacketData>) -> usize {
         .fold(1, |acc, i| acc * i)
 }
 
+pub(crate) struct Part1;
+pub(crate) struct Part2;
+
+impl Solution for Part1 {
+    type Parsed = Vec<(PacketData, PacketData)>;
+    type Output = usize;
+
+    fn parse(&self, input: Vec<String>) -> Self::Parsed {
+        parse(input.into_iter())
+    }
+
+    fn solve(&self, packets: Self::Parsed) -> Self::Output {
+        sum_ok_pairs(packets)
+    }
+}
+
+impl Solution for Part2 {
+    type Parsed = Vec<(PacketData, PacketData)>;
+    type Output = usize;
+
+    fn parse(&self, input: Vec<String>) -> Self::Parsed {
+        parse(input.into_iter())
+    }
+
+    fn solve(&self, packets: Self::Parsed) -> Self::Output {
+        find_dividers(sort_packets(packets))
+    }
+}
+
+pub fn aoc_13_1() {
+    let input = get_input("resource/aoc_13/data.txt");
+
+    let sum = Part1.solve(Part1.parse(input));
+
+    println!("AOC-13-1 sum ok pairs: {}", &sum)
+}
+
 pub fn aoc_13_2() {
     let input = get_input("resource/aoc_13/data.txt");
-    let packets = parse(input.into_iter());
 
-    let divs = find_dividers(sort_packets(packets));
+    let divs = Part2.solve(Part2.parse(input));
 
     println!("AOC-13-2 dividers index product: {}", &divs)
 }
@@ -3,7 +3,7 @@ use std::collections::HashMap;
 use lazy_static::lazy_static;
 use regex::Regex;
 
-use crate::get_input;
+use crate::{get_input, runner::Solution};
 
 #[derive(Debug)]
 enum SquareContent {
@@ -23,7 +23,7 @@ impl Position {
     }
 }
 
-struct Grid {
+pub(crate) struct Grid {
     content: HashMap<Position, SquareContent>,
     floor: usize,
     infinite_floor: bool,
@@ -175,21 +175,49 @@ fn parse_line(line: String) -> Vec<Position> {
     res
 }
 
+pub(crate) struct Part1;
+pub(crate) struct Part2;
+
+impl Solution for Part1 {
+    type Parsed = Grid;
+    type Output = usize;
+
+    fn parse(&self, input: Vec<String>) -> Self::Parsed {
+        parse(input.into_iter())
+    }
+
+    fn solve(&self, mut grid: Self::Parsed) -> Self::Output {
+        drop_sand(&mut grid)
+    }
+}
+
+impl Solution for Part2 {
+    type Parsed = Grid;
+    type Output = usize;
+
+    fn parse(&self, input: Vec<String>) -> Self::Parsed {
+        parse(input.into_iter())
+    }
+
+    fn solve(&self, mut grid: Self::Parsed) -> Self::Output {
+        grid.floor = grid.floor + 2;
+        grid.infinite_floor = true;
+
+        fill_with_sand(&mut grid)
+    }
+}
+
 pub fn aoc_14_1() {
     let input = get_input("resource/aoc_14/data.txt");
-    let mut grid = parse(input.into_iter());
 
-    let sand_count = drop_sand(&mut grid);
+    let sand_count = Part1.solve(Part1.parse(input));
 
     println!("AOC-14-1 sand count : {}", sand_count);
 }
 pub fn aoc_14_2() {
     let input = get_input("resource/aoc_14/data.txt");
-    let mut grid = parse(input.into_iter());
 
-    grid.floor = grid.floor + 2;
-    grid.infinite_floor = true;
-    let sand_count = fill_with_sand(&mut grid);
+    let sand_count = Part2.solve(Part2.parse(input));
 
     println!("AOC-14-2 fill sand count : {}", sand_count);
 }
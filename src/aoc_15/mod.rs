@@ -3,7 +3,7 @@ use std::collections::{HashMap, HashSet};
 use lazy_static::lazy_static;
 use regex::Regex;
 
-use crate::get_input;
+use crate::{get_input, runner::Solution};
 
 type Distance = u32;
 
@@ -171,7 +171,7 @@ impl Circle {
     }
 }
 
-struct Field {
+pub(crate) struct Field {
     circles: Vec<Circle>,
     min_x: i32,
     max_x: i32,
@@ -285,36 +285,65 @@ fn parse<I: Iterator<Item = String>>(input: I) -> Field {
     }
 }
 
+pub(crate) struct Part1;
+pub(crate) struct Part2;
+
+impl Solution for Part1 {
+    type Parsed = Field;
+    type Output = i32;
+
+    fn parse(&self, input: Vec<String>) -> Self::Parsed {
+        parse(input.into_iter())
+    }
+
+    fn solve(&self, field: Self::Parsed) -> Self::Output {
+        field.count_unchecked(2000000)
+    }
+}
+
+impl Solution for Part2 {
+    type Parsed = Field;
+    type Output = i64;
+
+    fn parse(&self, input: Vec<String>) -> Self::Parsed {
+        parse(input.into_iter())
+    }
+
+    fn solve(&self, field: Self::Parsed) -> Self::Output {
+        let uncovered = field.find_uncovered(Interval::new(0, 4000000), Interval::new(0, 4000000));
+
+        assert!(uncovered.len() == 1);
+        let pos: Vec<(i64, i64)> = uncovered
+            .into_iter()
+            .map(|(y, intervals)| {
+                assert!(intervals.len() == 1);
+                let i = intervals[0];
+                assert!(i.low == i.high);
+                (i.low as i64, y as i64)
+            })
+            .collect();
+
+        assert!(pos.len() == 1);
+
+        let pos = pos[0];
+
+        pos.0 * 4000000 + pos.1
+    }
+}
+
 pub fn aoc_15_1() {
     let input = get_input("resource/aoc_15/data.txt");
-    let field = parse(input.into_iter());
 
-    let unchecked = field.count_unchecked(2000000);
+    let unchecked = Part1.solve(Part1.parse(input));
 
     println!("AOC-15-1 unchecked count: {}", unchecked)
 }
 pub fn aoc_15_2() {
     let input = get_input("resource/aoc_15/data.txt");
-    let field = parse(input.into_iter());
-
-    let uncovered = field.find_uncovered(Interval::new(0, 4000000), Interval::new(0, 4000000));
-
-    assert!(uncovered.len() == 1);
-    let pos: Vec<(i64, i64)> = uncovered
-        .into_iter()
-        .map(|(y, intervals)| {
-            assert!(intervals.len() == 1);
-            let i = intervals[0];
-            assert!(i.low == i.high);
-            (i.low as i64, y as i64)
-        })
-        .collect();
-
-    assert!(pos.len() == 1);
 
-    let pos = pos[0];
+    let freq = Part2.solve(Part2.parse(input));
 
-    println!("AOC-15-2 beacon freq: {}", pos.0 * 4000000 + pos.1)
+    println!("AOC-15-2 beacon freq: {}", freq)
 }
 
 #[cfg(test)]
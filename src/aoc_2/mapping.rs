@@ -0,0 +1,264 @@
+use std::fmt::Display;
+
+use itertools::Itertools;
+
+use super::{split_columns, FightResult, Game, Score, Shape};
+
+/// What a code of the second column could stand for
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Meaning {
+    Shape(Shape),
+    Outcome(FightResult),
+}
+
+/// One interpretation of the second column, every code of the guide mapped to a distinct meaning
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Mapping {
+    pub codes: Vec<(String, Meaning)>,
+}
+
+impl Mapping {
+    fn meaning(&self, code: &str) -> Meaning {
+        self.codes
+            .iter()
+            .find(|(c, _)| c == code)
+            .map(|(_, m)| *m)
+            .expect("Every code of the guide is mapped")
+    }
+
+    pub fn describe(&self, game: &Game) -> String {
+        self.codes
+            .iter()
+            .map(|(code, meaning)| match meaning {
+                Meaning::Shape(shape) => format!("{}={}", code, game.name(*shape)),
+                Meaning::Outcome(outcome) => format!("{}={:?}", code, outcome),
+            })
+            .join(" ")
+    }
+}
+
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Scored {
+    pub mapping: Mapping,
+    pub score: Score,
+}
+
+/// Every mapping of the second column with the total score of the guide under it,
+/// best score first
+pub struct Analysis<'a> {
+    game: &'a Game,
+    scored: Vec<Scored>,
+}
+
+/// The guide as opponent shapes and raw second column codes
+fn parse_guide<I: Iterator<Item = String>>(
+    game: &Game,
+    guide: I,
+) -> Result<Vec<(Shape, String)>, String> {
+    guide
+        .enumerate()
+        .filter(|(_, line)| !line.is_empty())
+        .map(|(i, line)| {
+            let (other, code) =
+                split_columns(&line).map_err(|e| format!("Line {}: {}", i + 1, e))?;
+            let other = game.opponent_shape(other).ok_or(format!(
+                "Line {}: unknown opponent code {}",
+                i + 1,
+                other
+            ))?;
+            Ok((other, code.to_string()))
+        })
+        .collect()
+}
+
+/// Injective mappings of `codes` to shapes, then to outcomes when there are at most 3 codes
+fn mappings(game: &Game, codes: &[String]) -> Vec<Mapping> {
+    let shapes: Vec<Meaning> = game.shapes().map(Meaning::Shape).collect();
+    let outcomes: Vec<Meaning> = [FightResult::Lose, FightResult::Draw, FightResult::Win]
+        .into_iter()
+        .map(Meaning::Outcome)
+        .collect();
+
+    [shapes, outcomes]
+        .into_iter()
+        .filter(|meanings| codes.len() <= meanings.len())
+        .flat_map(|meanings| meanings.into_iter().permutations(codes.len()))
+        .map(|meanings| Mapping {
+            codes: codes.iter().cloned().zip(meanings).collect(),
+        })
+        .collect()
+}
+
+fn score(game: &Game, rounds: &[(Shape, String)], mapping: &Mapping) -> Score {
+    rounds
+        .iter()
+        .map(|(other, code)| match mapping.meaning(code) {
+            Meaning::Shape(me) => game.play(me, *other),
+            Meaning::Outcome(outcome) => game.play(game.strategy(*other, outcome), *other),
+        })
+        .sum()
+}
+
+pub fn analyze<I: Iterator<Item = String>>(game: &Game, guide: I) -> Result<Analysis<'_>, String> {
+    let rounds = parse_guide(game, guide)?;
+    if rounds.is_empty() {
+        return Err("Empty guide".to_string());
+    }
+    let codes: Vec<String> = rounds
+        .iter()
+        .map(|(_, code)| code.clone())
+        .sorted()
+        .dedup()
+        .collect();
+
+    let mut scored: Vec<Scored> = mappings(game, &codes)
+        .into_iter()
+        .map(|mapping| Scored {
+            score: score(game, &rounds, &mapping),
+            mapping,
+        })
+        .collect();
+    if scored.is_empty() {
+        return Err(format!(
+            "{} codes in the second column, more than the {} shapes",
+            codes.len(),
+            game.shapes().count()
+        ));
+    }
+    scored.sort_by_key(|s| std::cmp::Reverse(s.score)); //stable, so ties keep enumeration order
+
+    Ok(Analysis { game, scored })
+}
+
+impl Analysis<'_> {
+    pub fn best(&self) -> Option<&Scored> {
+        self.scored.first()
+    }
+
+    pub fn worst(&self) -> Option<&Scored> {
+        self.scored.last()
+    }
+
+    pub fn all(&self) -> &[Scored] {
+        &self.scored
+    }
+
+    /// Score of the guide under a given mapping, `None` if that mapping is not a candidate
+    pub fn score_of(&self, mapping: &Mapping) -> Option<Score> {
+        self.scored
+            .iter()
+            .find(|s| &s.mapping == mapping)
+            .map(|s| s.score)
+    }
+}
+
+impl Display for Analysis<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let line = |s: &Scored| format!("{} ({})", s.score, s.mapping.describe(self.game));
+
+        if let (Some(best), Some(worst)) = (self.best(), self.worst()) {
+            writeln!(f, "Best: {}", line(best))?;
+            writeln!(f, "Worst: {}", line(worst))?;
+        }
+
+        writeln!(f, "All {} mappings:", self.scored.len())?;
+        for s in self.scored.iter() {
+            writeln!(f, "  {}", line(s))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn guide() -> impl Iterator<Item = String> {
+        ["A Y", "B X", "C Z"].iter().map(|s| s.to_string())
+    }
+
+    #[test]
+    fn test_puzzle_mappings() {
+        let game = Game::classic();
+        let analysis = analyze(game, guide()).unwrap();
+
+        assert_eq!(6 + 6, analysis.all().len());
+
+        let as_shapes = Mapping {
+            codes: ["X", "Y", "Z"]
+                .iter()
+                .map(|c| (c.to_string(), Meaning::Shape(game.own_shape(c).unwrap())))
+                .collect(),
+        };
+        let as_outcomes = Mapping {
+            codes: ["X", "Y", "Z"]
+                .iter()
+                .map(|c| (c.to_string(), Meaning::Outcome(game.outcome(c).unwrap())))
+                .collect(),
+        };
+
+        assert_eq!(Some(15), analysis.score_of(&as_shapes));
+        assert_eq!(Some(12), analysis.score_of(&as_outcomes));
+
+        let scores: Vec<Score> = analysis.all().iter().map(|s| s.score).collect();
+        assert_eq!(Some(&analysis.best().unwrap().score), scores.iter().max());
+        assert_eq!(Some(&analysis.worst().unwrap().score), scores.iter().min());
+        assert_eq!(24, analysis.best().unwrap().score); //winning every round
+    }
+
+    #[test]
+    fn test_codes_from_guide() {
+        let game = Game::load("resource/aoc_2/rpsls.rules").unwrap();
+        let guide = ["A Q", "B Q", "C R", "D S", "E T"]
+            .iter()
+            .map(|s| s.to_string());
+
+        let analysis = analyze(&game, guide).unwrap();
+
+        //4 codes: 5*4*3*2 shape mappings, too many codes to stand for outcomes
+        assert_eq!(120, analysis.all().len());
+        assert!(analysis.all().iter().all(|s| s
+            .mapping
+            .codes
+            .iter()
+            .map(|(c, _)| c)
+            .eq(["Q", "R", "S", "T"].iter())));
+    }
+
+    #[test]
+    fn test_report() {
+        let analysis = analyze(Game::classic(), guide()).unwrap();
+        let report = analysis.to_string();
+
+        assert!(report.starts_with("Best: 24 ("));
+        assert!(report.contains("All 12 mappings:"));
+
+        let unknown = ["F X"].iter().map(|s| s.to_string());
+        assert!(analyze(Game::classic(), unknown).is_err());
+    }
+
+    #[test]
+    fn test_no_mapping() {
+        let empty = analyze(Game::classic(), std::iter::empty());
+        assert_eq!(Some("Empty guide".to_string()), empty.err());
+
+        let blank = analyze(Game::classic(), [""].iter().map(|s| s.to_string()));
+        assert_eq!(Some("Empty guide".to_string()), blank.err());
+
+        let four_codes = ["A W", "A X", "B Y", "C Z"].iter().map(|s| s.to_string());
+        assert_eq!(
+            Some("4 codes in the second column, more than the 3 shapes".to_string()),
+            analyze(Game::classic(), four_codes).err()
+        );
+    }
+
+    #[test]
+    fn test_invalid_line() {
+        let guide = ["A Y", "", "B X Z"].iter().map(|s| s.to_string());
+
+        match analyze(Game::classic(), guide) {
+            Err(e) => assert_eq!("Line 3: Expected two columns in \"B X Z\"", e),
+            Ok(_) => panic!("Three columns should be rejected"),
+        }
+    }
+}
@@ -1,134 +1,357 @@
-use crate::get_input;
+pub mod mapping;
+pub mod tournament;
 
+use std::{fs, path::Path};
 
-pub fn aoc_2_1(){
-    let input = get_input("resource/aoc_2/data.txt");
+use lazy_static::lazy_static;
 
-    let mut total_score: Score = 0;
+use crate::{
+    runner::{Lines, Solution},
+    stream_input,
+};
 
-    for line in input.iter() {
-        let split: Vec<&str> = line.split(" ").collect();
-        assert!(split.len() == 2);
+pub(crate) struct Part1;
+pub(crate) struct Part2;
 
-        let me: RPS = split[1].into();
-        let other: RPS = split[0].into();
+lazy_static! {
+    static ref CLASSIC: Game = Game::parse(include_str!("../../resource/aoc_2/rps.rules").lines())
+        .expect("Classic rules should be correct");
+}
 
-        let score = me.play(&other);
+/// Rounds of the strategy guide, parsed lazily: the opponent shape and the second column
+pub(crate) type Rounds<T> = Box<dyn Iterator<Item = (Shape, T)>>;
 
-        total_score += score;
+fn split_columns(line: &str) -> Result<(&str, &str), String> {
+    let split: Vec<&str> = line.split(" ").collect();
+    if split.len() != 2 {
+        return Err(format!("Expected two columns in {:?}", line));
     }
-    
-    println!("AOC-2-1 total score: {}", &total_score);
+
+    Ok((split[0], split[1]))
 }
 
-pub fn aoc_2_2(){
-    let input = get_input("resource/aoc_2/data.txt");
+fn parse_rounds<T, F>(game: &'static Game, lines: Lines, second: F) -> Rounds<T>
+where
+    F: Fn(&Game, &str) -> Option<T> + 'static,
+{
+    Box::new(lines.map(move |line| {
+        let (other, code) = split_columns(&line).unwrap_or_else(|e| panic!("{}", e));
+        (
+            game.opponent_shape(other)
+                .unwrap_or_else(|| panic!("Unknown opponent code {}", other)),
+            second(game, code).unwrap_or_else(|| panic!("Unknown code {}", code)),
+        )
+    }))
+}
+
+impl Solution for Part1 {
+    type Parsed = Rounds<Shape>;
+    type Output = Score;
 
-    let mut total_score: Score = 0;
+    const STREAMING: bool = true;
 
-    for line in input.iter() {
-        let split: Vec<&str> = line.split(" ").collect();
-        assert!(split.len() == 2);
+    fn parse(&self, input: Vec<String>) -> Self::Parsed {
+        self.parse_lines(Box::new(input.into_iter()))
+    }
+
+    fn parse_lines(&self, lines: Lines) -> Self::Parsed {
+        parse_rounds(&CLASSIC, lines, Game::own_shape)
+    }
+
+    fn solve(&self, parsed: Self::Parsed) -> Self::Output {
+        parsed.map(|(other, me)| CLASSIC.play(me, other)).sum()
+    }
+}
 
-        let outcome: FightResult = split[1].into();
-        let other: RPS = split[0].into();
+impl Solution for Part2 {
+    type Parsed = Rounds<FightResult>;
+    type Output = Score;
 
-        let score = outcome.score() + strategy(&other, &outcome).score();
+    const STREAMING: bool = true;
 
-        total_score += score;
+    fn parse(&self, input: Vec<String>) -> Self::Parsed {
+        self.parse_lines(Box::new(input.into_iter()))
     }
-    
+
+    fn parse_lines(&self, lines: Lines) -> Self::Parsed {
+        parse_rounds(&CLASSIC, lines, Game::outcome)
+    }
+
+    fn solve(&self, parsed: Self::Parsed) -> Self::Output {
+        parsed
+            .map(|(other, outcome)| CLASSIC.play(CLASSIC.strategy(other, outcome), other))
+            .sum()
+    }
+}
+
+pub fn aoc_2_1() {
+    let input = stream_input("resource/aoc_2/data.txt").expect("Cannot read file");
+
+    let total_score = Part1.solve(Part1.parse_lines(Box::new(input)));
+
+    println!("AOC-2-1 total score: {}", &total_score);
+}
+
+pub fn aoc_2_2() {
+    let input = stream_input("resource/aoc_2/data.txt").expect("Cannot read file");
+
+    let total_score = Part2.solve(Part2.parse_lines(Box::new(input)));
+
     println!("AOC-2-2 total score: {}", &total_score);
 }
 
-#[derive(PartialEq,Eq,Clone, Debug)]
-enum RPS {
-    Rock, Paper, Scissors
+pub type Score = u64;
+
+/// A shape of a `Game`, identified by its position in the game cycle
+#[derive(PartialEq, Eq, Clone, Copy, Hash, Debug)]
+pub struct Shape(usize);
+
+#[derive(PartialEq, Eq, Clone, Copy, Hash, Debug)]
+pub enum FightResult {
+    Win,
+    Lose,
+    Draw,
 }
 
-enum FightResult {
-    Win, Lose, Draw
+#[derive(Debug, Clone)]
+struct ShapeRule {
+    name: String,
+    opponent_code: String,
+    own_code: String,
+    score: Score,
 }
 
-type Score = u64;
+type FieldGetter = fn(&ShapeRule) -> &str;
 
-impl From<&str> for RPS {
-    fn from(s: &str) -> Self {
-        match s {
-            "A" | "X" => RPS::Rock,
-            "B" | "Y" => RPS::Paper,
-            "C" | "Z" => RPS::Scissors,
-            _ => unreachable!()
-        }
-    }
+#[derive(Debug, Clone)]
+struct OutcomeRule {
+    result: FightResult,
+    code: String,
+    score: Score,
 }
 
-impl RPS {
-    fn fight(&self,other : &RPS) ->  FightResult {
-        match (self,other) {
-            (me,other) if me.beats() == *other => FightResult::Win,
-            (me, other) if me == other  => FightResult::Draw,
-            (_,_) => FightResult::Lose
-        }
+/// A cyclic hand game with an odd number of shapes, Rock-Paper-Scissors being the smallest.
+/// Shapes are kept in cycle order: each one beats the (n-1)/2 shapes just before it
+/// and is beaten by the (n-1)/2 shapes just after it.
+#[derive(Debug, Clone)]
+pub struct Game {
+    shapes: Vec<ShapeRule>,
+    outcomes: Vec<OutcomeRule>,
+}
+
+impl Game {
+    /// The puzzle rules: Rock, Paper, Scissors
+    pub fn classic() -> &'static Game {
+        &CLASSIC
     }
 
-    fn score(&self) -> Score {
-        match self {
-            RPS::Rock => 1,
-            RPS::Paper => 2,
-            RPS::Scissors => 3
+    pub fn load<T: AsRef<Path> + ?Sized>(path: &T) -> Result<Game, String> {
+        let data = fs::read_to_string(path).map_err(|e| format!("Cannot read rules {}", e))?;
+
+        Game::parse(data.lines())
+    }
+
+    /// One rule per line, blank lines and lines starting with # are ignored:
+    /// `shape <name> <opponent code> <own code> <score>` in cycle order,
+    /// `outcome <lose|draw|win> <code> <score>`
+    pub fn parse<I: Iterator<Item = S>, S: AsRef<str>>(lines: I) -> Result<Game, String> {
+        let mut shapes: Vec<ShapeRule> = vec![];
+        let mut outcomes: Vec<OutcomeRule> = vec![];
+
+        for (line_nb, line) in lines.enumerate() {
+            let line = line.as_ref().trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let words: Vec<&str> = line.split_whitespace().collect();
+            let parse_score = |s: &str| {
+                s.parse::<Score>()
+                    .map_err(|_| format!("Line {}: score is not a number {}", line_nb + 1, s))
+            };
+
+            match words.as_slice() {
+                ["shape", name, opponent_code, own_code, score] => shapes.push(ShapeRule {
+                    name: name.to_string(),
+                    opponent_code: opponent_code.to_string(),
+                    own_code: own_code.to_string(),
+                    score: parse_score(score)?,
+                }),
+                ["outcome", result, code, score] => {
+                    let result = match *result {
+                        "lose" => FightResult::Lose,
+                        "draw" => FightResult::Draw,
+                        "win" => FightResult::Win,
+                        other => {
+                            return Err(format!("Line {}: unknown outcome {}", line_nb + 1, other))
+                        }
+                    };
+                    outcomes.push(OutcomeRule {
+                        result,
+                        code: code.to_string(),
+                        score: parse_score(score)?,
+                    })
+                }
+                _ => return Err(format!("Line {}: cannot parse rule {}", line_nb + 1, line)),
+            }
         }
+
+        Game::validate(&shapes, &outcomes)?;
+
+        Ok(Game { shapes, outcomes })
     }
 
-    fn beats(&self) -> RPS {
-        match self {
-            RPS::Rock => RPS::Scissors,
-            RPS::Paper => RPS::Rock,
-            RPS::Scissors => RPS::Paper
+    #[allow(clippy::manual_is_multiple_of)]
+    fn validate(shapes: &[ShapeRule], outcomes: &[OutcomeRule]) -> Result<(), String> {
+        if shapes.len() < 3 || shapes.len() % 2 == 0 {
+            return Err(format!(
+                "A cyclic game needs an odd number of shapes, at least 3, got {}",
+                shapes.len()
+            ));
+        }
+
+        let fields: [(&str, FieldGetter); 3] = [
+            ("name", |s| &s.name),
+            ("opponent code", |s| &s.opponent_code),
+            ("own code", |s| &s.own_code),
+        ];
+        for (field, get) in fields {
+            for (i, shape) in shapes.iter().enumerate() {
+                if shapes[..i].iter().any(|other| get(other) == get(shape)) {
+                    return Err(format!("Duplicate shape {} {}", field, get(shape)));
+                }
+            }
+        }
+
+        for result in [FightResult::Lose, FightResult::Draw, FightResult::Win] {
+            let count = outcomes.iter().filter(|o| o.result == result).count();
+            if count != 1 {
+                return Err(format!("Expect one rule for {:?}, got {}", result, count));
+            }
+        }
+
+        for (i, outcome) in outcomes.iter().enumerate() {
+            if outcomes[..i].iter().any(|other| other.code == outcome.code) {
+                return Err(format!("Duplicate outcome code {}", outcome.code));
+            }
         }
+
+        Ok(())
+    }
+
+    pub fn shapes(&self) -> impl Iterator<Item = Shape> {
+        (0..self.shapes.len()).map(Shape)
+    }
+
+    pub fn name(&self, shape: Shape) -> &str {
+        &self.shapes[shape.0].name
+    }
+
+    pub fn shape(&self, name: &str) -> Option<Shape> {
+        self.shapes.iter().position(|s| s.name == name).map(Shape)
+    }
+
+    pub fn opponent_shape(&self, code: &str) -> Option<Shape> {
+        self.shapes
+            .iter()
+            .position(|s| s.opponent_code == code)
+            .map(Shape)
+    }
+
+    pub fn own_shape(&self, code: &str) -> Option<Shape> {
+        self.shapes
+            .iter()
+            .position(|s| s.own_code == code)
+            .map(Shape)
     }
 
-    fn is_beat_by(&self) -> RPS {
-        match self {
-            RPS::Rock => RPS::Paper,
-            RPS::Paper => RPS::Scissors,
-            RPS::Scissors => RPS::Rock
+    pub fn outcome(&self, code: &str) -> Option<FightResult> {
+        self.outcomes
+            .iter()
+            .find(|o| o.code == code)
+            .map(|o| o.result)
+    }
+
+    pub fn shape_score(&self, shape: Shape) -> Score {
+        self.shapes[shape.0].score
+    }
+
+    pub fn outcome_score(&self, result: FightResult) -> Score {
+        self.outcomes
+            .iter()
+            .find(|o| o.result == result)
+            .map(|o| o.score)
+            .expect("Every outcome has a rule")
+    }
+
+    /// How far `to` is after `from` in the cycle
+    fn distance(&self, from: Shape, to: Shape) -> usize {
+        (to.0 + self.shapes.len() - from.0) % self.shapes.len()
+    }
+
+    pub fn fight(&self, me: Shape, other: Shape) -> FightResult {
+        match self.distance(other, me) {
+            0 => FightResult::Draw,
+            d if d <= self.shapes.len() / 2 => FightResult::Win,
+            _ => FightResult::Lose,
         }
     }
 
-    fn play(&self, other: &RPS) -> Score {
-        self.score() + self.fight(&other).score()
+    pub fn play(&self, me: Shape, other: Shape) -> Score {
+        self.shape_score(me) + self.outcome_score(self.fight(me, other))
     }
-}
 
-impl FightResult {
-    fn score(&self) -> Score {
-        match self {
-            FightResult::Win => 6,
-            FightResult::Draw => 3,
-            FightResult::Lose => 0
+    /// The shape beaten by `shape` that is nearest in the cycle
+    pub fn beats(&self, shape: Shape) -> Shape {
+        Shape((shape.0 + self.shapes.len() - 1) % self.shapes.len())
+    }
+
+    /// The shape beating `shape` that is nearest in the cycle
+    pub fn is_beat_by(&self, shape: Shape) -> Shape {
+        Shape((shape.0 + 1) % self.shapes.len())
+    }
+
+    /// Returns the move needed to reach outcome given other
+    pub fn strategy(&self, other: Shape, outcome: FightResult) -> Shape {
+        match outcome {
+            FightResult::Draw => other,
+            FightResult::Lose => self.beats(other),
+            FightResult::Win => self.is_beat_by(other),
         }
     }
 }
 
-impl From<&str> for FightResult {
-    fn from(s: &str) -> Self {
-        match s {
-            "X" => FightResult::Lose,
-            "Y" => FightResult::Draw,
-            "Z" => FightResult::Win,
-            _ => unreachable!()
-        }
+/// Total score of a guide when the second column is the shape to play
+pub fn score_as_shapes<I: Iterator<Item = String>>(game: &Game, guide: I) -> Result<Score, String> {
+    let mut total = 0;
+    for line in guide {
+        let (other, me) = split_columns(&line)?;
+        let other = game
+            .opponent_shape(other)
+            .ok_or(format!("Unknown opponent code {}", other))?;
+        let me = game.own_shape(me).ok_or(format!("Unknown code {}", me))?;
+        total += game.play(me, other);
     }
+    Ok(total)
 }
 
-/// Returns the move needed to reach outcome given other
-fn strategy(other: &RPS, outcome: &FightResult) -> RPS {
-    match (outcome, other) {
-        (FightResult::Draw, o) => o.clone(),
-        (FightResult::Lose, o) => o.beats(),
-        (FightResult::Win, o) => o.is_beat_by()
+/// Total score of a guide when the second column is the outcome to reach
+pub fn score_as_outcomes<I: Iterator<Item = String>>(
+    game: &Game,
+    guide: I,
+) -> Result<Score, String> {
+    let mut total = 0;
+    for line in guide {
+        let (other, outcome) = split_columns(&line)?;
+        let other = game
+            .opponent_shape(other)
+            .ok_or(format!("Unknown opponent code {}", other))?;
+        let outcome = game
+            .outcome(outcome)
+            .ok_or(format!("Unknown outcome code {}", outcome))?;
+        total += game.play(game.strategy(other, outcome), other);
     }
+    Ok(total)
 }
 
 #[cfg(test)]
@@ -137,37 +360,136 @@ mod tests {
 
     use super::*;
 
-    use super::RPS::{Rock,Paper,Scissors};
+    fn shapes(game: &Game, names: &[&str]) -> Vec<Shape> {
+        names.iter().map(|n| game.shape(n).unwrap()).collect()
+    }
+
+    #[test]
+    fn test_rps() {
+        let game = Game::classic();
+        let me = shapes(game, &["Rock", "Paper", "Scissors"]);
+        let other = me.clone();
+
+        let all_scores: Vec<Score> = me
+            .iter()
+            .cartesian_product(other)
+            .map(|(m, o)| game.play(*m, o))
+            .collect();
+
+        itertools::assert_equal(all_scores, vec![4, 1, 7, 8, 5, 2, 3, 9, 6]);
+    }
+
+    #[test]
+    fn test_strat() {
+        let game = Game::classic();
+        let me = [FightResult::Win, FightResult::Draw, FightResult::Lose];
+        let other = shapes(game, &["Rock", "Paper", "Scissors"]);
+
+        let all_moves: Vec<&str> = me
+            .iter()
+            .cartesian_product(other)
+            .map(|(outcome, other)| game.name(game.strategy(other, *outcome)))
+            .collect();
+
+        itertools::assert_equal(
+            all_moves,
+            vec![
+                "Paper", "Scissors", "Rock", "Rock", "Paper", "Scissors", "Scissors", "Rock",
+                "Paper",
+            ],
+        );
+    }
 
     #[test]
-    fn test_rps(){
-        let me = vec![RPS::Rock,RPS::Paper,RPS::Scissors];
-        let other = vec![RPS::Rock,RPS::Paper,RPS::Scissors];
+    fn test_rpsls() {
+        let game = Game::load("resource/aoc_2/rpsls.rules").unwrap();
+
+        let wins = [
+            ("Scissors", "Paper"),
+            ("Paper", "Rock"),
+            ("Rock", "Lizard"),
+            ("Lizard", "Spock"),
+            ("Spock", "Scissors"),
+            ("Scissors", "Lizard"),
+            ("Lizard", "Paper"),
+            ("Paper", "Spock"),
+            ("Spock", "Rock"),
+            ("Rock", "Scissors"),
+        ];
+
+        for (winner, loser) in wins {
+            let (winner, loser) = (game.shape(winner).unwrap(), game.shape(loser).unwrap());
+            assert_eq!(FightResult::Win, game.fight(winner, loser));
+            assert_eq!(FightResult::Lose, game.fight(loser, winner));
+        }
 
-        let all_scores:Vec<Score> = me.iter().cartesian_product(other).map(|(m,o)| m.play(&o)).collect();
+        for shape in game.shapes() {
+            assert_eq!(FightResult::Draw, game.fight(shape, shape));
+            for outcome in [FightResult::Win, FightResult::Draw, FightResult::Lose] {
+                assert_eq!(outcome, game.fight(game.strategy(shape, outcome), shape));
+            }
+        }
 
-        itertools::assert_equal(all_scores, vec![4,1,7,8,5,2,3,9,6]);
+        let spock = game.shape("Spock").unwrap();
+        let rock = game.shape("Rock").unwrap();
+        assert_eq!(11, game.play(spock, rock));
+
+        let guide = ["A W", "E W", "C Z"].iter().map(|s| s.to_string());
+        assert_eq!(Ok(11 + 11 + 3 + 6), score_as_shapes(&game, guide));
     }
 
     #[test]
-    fn test_strat(){
-        let me = vec![FightResult::Win,FightResult::Draw,FightResult::Lose];
-        let other = vec![RPS::Rock,RPS::Paper,RPS::Scissors];
+    fn test_guide_scores() {
+        let guide = || ["A Y", "B X", "C Z"].iter().map(|s| s.to_string());
+
+        assert_eq!(Ok(15), score_as_shapes(Game::classic(), guide()));
+        assert_eq!(Ok(12), score_as_outcomes(Game::classic(), guide()));
 
-        let all_scores:Vec<RPS> = me.iter().cartesian_product(other).map(|(outcome,other)| strategy(&other, outcome)).collect();
+        let bad_guide = ["A Q"].iter().map(|s| s.to_string());
+        assert!(score_as_shapes(Game::classic(), bad_guide).is_err());
+    }
 
-        itertools::assert_equal(all_scores, vec![Paper, Scissors, Rock, Rock, Paper, Scissors, Scissors, Rock, Paper]);
+    #[test]
+    fn test_invalid_rules() {
+        let even = ["shape Rock A X 1", "shape Paper B Y 2"];
+        assert!(Game::parse(even.iter()).is_err());
+
+        let missing_outcome = [
+            "shape Rock A X 1",
+            "shape Paper B Y 2",
+            "shape Scissors C Z 3",
+            "outcome win Z 6",
+        ];
+        assert!(Game::parse(missing_outcome.iter()).is_err());
+
+        let duplicate_code = [
+            "shape Rock A X 1",
+            "shape Paper A Y 2",
+            "shape Scissors C Z 3",
+        ];
+        assert!(Game::parse(duplicate_code.iter()).is_err());
+
+        let duplicate_outcome_code = [
+            "shape Rock A X 1",
+            "shape Paper B Y 2",
+            "shape Scissors C Z 3",
+            "outcome lose X 0",
+            "outcome draw Y 3",
+            "outcome win X 6",
+        ];
+        assert_eq!(
+            Some("Duplicate outcome code X".to_string()),
+            Game::parse(duplicate_outcome_code.iter()).err()
+        );
     }
 
     #[test]
-    fn test_aoc_2_1(){
+    fn test_aoc_2_1() {
         aoc_2_1()
     }
 
     #[test]
-    fn test_aoc_2_2(){
+    fn test_aoc_2_2() {
         aoc_2_2()
     }
-
-
-}
\ No newline at end of file
+}
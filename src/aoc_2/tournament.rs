@@ -0,0 +1,500 @@
+use std::{collections::HashMap, fmt::Display, fs, path::Path};
+
+use super::{split_columns, FightResult, Game, Score, Shape};
+
+/// A player of the tournament, choosing its next shape from the rounds already played
+/// in the current match, as (own shape, opponent shape)
+pub trait Strategy {
+    fn name(&self) -> &str;
+
+    /// Called before each match
+    fn reset(&mut self) {}
+
+    fn next(&mut self, game: &Game, history: &[(Shape, Shape)]) -> Shape;
+}
+
+/// Plays the second column of a strategy guide as shapes, starting over once exhausted
+pub struct Guide {
+    name: String,
+    shapes: Vec<Shape>,
+}
+
+impl Guide {
+    pub fn parse<I: Iterator<Item = String>>(
+        name: &str,
+        game: &Game,
+        lines: I,
+    ) -> Result<Guide, String> {
+        let shapes = lines
+            .enumerate()
+            .filter(|(_, line)| !line.is_empty())
+            .map(|(i, line)| {
+                let (_, code) =
+                    split_columns(&line).map_err(|e| format!("{}:{}: {}", name, i + 1, e))?;
+                game.own_shape(code)
+                    .ok_or(format!("{}:{}: unknown code {}", name, i + 1, code))
+            })
+            .collect::<Result<Vec<Shape>, String>>()?;
+
+        if shapes.is_empty() {
+            return Err(format!("{}: empty guide", name));
+        }
+
+        Ok(Guide {
+            name: name.to_string(),
+            shapes,
+        })
+    }
+
+    /// Named after the file
+    pub fn load<T: AsRef<Path> + ?Sized>(game: &Game, path: &T) -> Result<Guide, String> {
+        let path = path.as_ref();
+        let data = fs::read_to_string(path)
+            .map_err(|e| format!("Cannot read {}: {}", path.display(), e))?;
+
+        Guide::parse(
+            &path.display().to_string(),
+            game,
+            data.lines().map(String::from),
+        )
+    }
+}
+
+impl Strategy for Guide {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn next(&mut self, _game: &Game, history: &[(Shape, Shape)]) -> Shape {
+        self.shapes[history.len() % self.shapes.len()]
+    }
+}
+
+/// Beats the shape the opponent played the most so far
+pub struct FrequencyCounter;
+
+impl Strategy for FrequencyCounter {
+    fn name(&self) -> &str {
+        "frequency counter"
+    }
+
+    fn next(&mut self, game: &Game, history: &[(Shape, Shape)]) -> Shape {
+        let mut counts: HashMap<Shape, usize> = HashMap::new();
+        for (_, other) in history {
+            *counts.entry(*other).or_default() += 1;
+        }
+
+        //on a tie the first shape of the game wins, so the choice does not depend on hashing
+        let favourite = game
+            .shapes()
+            .max_by_key(|s| (counts.get(s).copied().unwrap_or(0), std::cmp::Reverse(s.0)));
+
+        game.is_beat_by(favourite.expect("A game has shapes"))
+    }
+}
+
+/// Plays whatever the opponent played last round
+pub struct Copycat;
+
+impl Strategy for Copycat {
+    fn name(&self) -> &str {
+        "copycat"
+    }
+
+    fn next(&mut self, game: &Game, history: &[(Shape, Shape)]) -> Shape {
+        history
+            .last()
+            .map(|(_, other)| *other)
+            .unwrap_or_else(|| game.shapes().next().expect("A game has shapes"))
+    }
+}
+
+/// Uniformly random shapes from a xorshift generator, the same seed replays the same match
+pub struct Random {
+    name: String,
+    seed: u64,
+    state: u64,
+}
+
+impl Random {
+    pub fn new(seed: u64) -> Self {
+        let seed = seed.max(1); //xorshift is stuck on 0
+        Random {
+            name: format!("random ({})", seed),
+            seed,
+            state: seed,
+        }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+        self.state
+    }
+}
+
+impl Strategy for Random {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn reset(&mut self) {
+        self.state = self.seed;
+    }
+
+    fn next(&mut self, game: &Game, _history: &[(Shape, Shape)]) -> Shape {
+        let count = game.shapes().count() as u64;
+        let index = (self.next_u64() % count) as usize;
+        game.shapes().nth(index).expect("Index within the game")
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Round {
+    pub first: Shape,
+    pub second: Shape,
+    /// Seen from the first player
+    pub result: FightResult,
+    pub first_score: Score,
+    pub second_score: Score,
+}
+
+/// One match between two players, `first` and `second` are indexes in the tournament
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Match {
+    pub first: usize,
+    pub second: usize,
+    pub rounds: Vec<Round>,
+}
+
+impl Match {
+    pub fn first_score(&self) -> Score {
+        self.rounds.iter().map(|r| r.first_score).sum()
+    }
+
+    pub fn second_score(&self) -> Score {
+        self.rounds.iter().map(|r| r.second_score).sum()
+    }
+
+    /// Seen from the first player, the match is won on total score
+    pub fn result(&self) -> FightResult {
+        match self.first_score().cmp(&self.second_score()) {
+            std::cmp::Ordering::Greater => FightResult::Win,
+            std::cmp::Ordering::Less => FightResult::Lose,
+            std::cmp::Ordering::Equal => FightResult::Draw,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Standing {
+    pub name: String,
+    pub wins: usize,
+    pub draws: usize,
+    pub losses: usize,
+    pub score: Score,
+}
+
+impl Standing {
+    /// 3 per match won, 1 per draw
+    pub fn points(&self) -> usize {
+        3 * self.wins + self.draws
+    }
+}
+
+/// Round-robin: every player meets every other player once
+pub struct Tournament<'a> {
+    pub game: &'a Game,
+    pub rounds: usize,
+    pub players: Vec<Box<dyn Strategy>>,
+}
+
+pub struct Results {
+    pub names: Vec<String>,
+    pub matches: Vec<Match>,
+}
+
+impl Tournament<'_> {
+    fn play_match(&mut self, first: usize, second: usize) -> Match {
+        let game = self.game;
+        self.players[first].reset();
+        self.players[second].reset();
+
+        let mut first_history: Vec<(Shape, Shape)> = Vec::with_capacity(self.rounds);
+        let mut second_history: Vec<(Shape, Shape)> = Vec::with_capacity(self.rounds);
+        let mut rounds = Vec::with_capacity(self.rounds);
+
+        for _ in 0..self.rounds {
+            let a = self.players[first].next(game, &first_history);
+            let b = self.players[second].next(game, &second_history);
+
+            rounds.push(Round {
+                first: a,
+                second: b,
+                result: game.fight(a, b),
+                first_score: game.play(a, b),
+                second_score: game.play(b, a),
+            });
+            first_history.push((a, b));
+            second_history.push((b, a));
+        }
+
+        Match {
+            first,
+            second,
+            rounds,
+        }
+    }
+
+    pub fn run(&mut self) -> Results {
+        let mut matches = vec![];
+        for first in 0..self.players.len() {
+            for second in first + 1..self.players.len() {
+                matches.push(self.play_match(first, second));
+            }
+        }
+
+        Results {
+            names: self.players.iter().map(|p| p.name().to_string()).collect(),
+            matches,
+        }
+    }
+}
+
+impl Results {
+    /// Best first: on points, then total score, then name
+    pub fn standings(&self) -> Vec<Standing> {
+        let mut standings: Vec<Standing> = self
+            .names
+            .iter()
+            .map(|name| Standing {
+                name: name.clone(),
+                ..Default::default()
+            })
+            .collect();
+
+        for m in self.matches.iter() {
+            standings[m.first].score += m.first_score();
+            standings[m.second].score += m.second_score();
+            match m.result() {
+                FightResult::Win => {
+                    standings[m.first].wins += 1;
+                    standings[m.second].losses += 1;
+                }
+                FightResult::Lose => {
+                    standings[m.first].losses += 1;
+                    standings[m.second].wins += 1;
+                }
+                FightResult::Draw => {
+                    standings[m.first].draws += 1;
+                    standings[m.second].draws += 1;
+                }
+            }
+        }
+
+        standings.sort_by(|a, b| {
+            b.points()
+                .cmp(&a.points())
+                .then(b.score.cmp(&a.score))
+                .then(a.name.cmp(&b.name))
+        });
+        standings
+    }
+
+    /// Round by round log of every match
+    pub fn log<'a>(&'a self, game: &'a Game) -> Log<'a> {
+        Log {
+            game,
+            results: self,
+        }
+    }
+}
+
+impl Display for Results {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let standings = self.standings();
+        let width = standings
+            .iter()
+            .map(|s| s.name.chars().count())
+            .max()
+            .unwrap_or(0)
+            .max("Player".len());
+
+        writeln!(
+            f,
+            "{:>4} | {:width$} | {:>3} | {:>3} | {:>3} | {:>6} | {:>8}",
+            "Rank",
+            "Player",
+            "W",
+            "D",
+            "L",
+            "Points",
+            "Score",
+            width = width
+        )?;
+        for (rank, s) in standings.iter().enumerate() {
+            writeln!(
+                f,
+                "{:>4} | {:width$} | {:>3} | {:>3} | {:>3} | {:>6} | {:>8}",
+                rank + 1,
+                s.name,
+                s.wins,
+                s.draws,
+                s.losses,
+                s.points(),
+                s.score,
+                width = width
+            )?;
+        }
+        Ok(())
+    }
+}
+
+pub struct Log<'a> {
+    game: &'a Game,
+    results: &'a Results,
+}
+
+impl Display for Log<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let names = &self.results.names;
+        for m in self.results.matches.iter() {
+            writeln!(f, "{} vs {}", names[m.first], names[m.second])?;
+            for (i, r) in m.rounds.iter().enumerate() {
+                writeln!(
+                    f,
+                    "  round {}: {} vs {} -> {:?} ({} - {})",
+                    i + 1,
+                    self.game.name(r.first),
+                    self.game.name(r.second),
+                    r.result,
+                    r.first_score,
+                    r.second_score
+                )?;
+            }
+            writeln!(
+                f,
+                "  total: {} - {} ({:?})",
+                m.first_score(),
+                m.second_score(),
+                m.result()
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn guide(name: &str, lines: &[&str]) -> Box<dyn Strategy> {
+        Box::new(Guide::parse(name, Game::classic(), lines.iter().map(|s| s.to_string())).unwrap())
+    }
+
+    #[test]
+    fn test_match() {
+        let mut tournament = Tournament {
+            game: Game::classic(),
+            rounds: 4,
+            players: vec![guide("rock", &["A X"]), guide("paper", &["A Y"])],
+        };
+
+        let results = tournament.run();
+        assert_eq!(1, results.matches.len());
+
+        let m = &results.matches[0];
+        assert_eq!(FightResult::Lose, m.rounds[0].result);
+        assert_eq!(4, m.first_score()); //Rock every round
+        assert_eq!(4 * (2 + 6), m.second_score());
+
+        let standings = results.standings();
+        assert_eq!("paper", standings[0].name);
+        assert_eq!(3, standings[0].points());
+        assert_eq!(1, standings[1].losses);
+    }
+
+    #[test]
+    fn test_adaptive() {
+        let game = Game::classic();
+        let rock = game.shape("Rock").unwrap();
+        let paper = game.shape("Paper").unwrap();
+        let scissors = game.shape("Scissors").unwrap();
+
+        let history = vec![(rock, scissors), (rock, scissors), (scissors, paper)];
+        assert_eq!(rock, FrequencyCounter.next(game, &history));
+        assert_eq!(paper, Copycat.next(game, &history));
+        assert_eq!(rock, Copycat.next(game, &[]));
+
+        //copycat always loses to a cycling guide after the first round
+        let mut tournament = Tournament {
+            game,
+            rounds: 9,
+            players: vec![guide("cycle", &["A X", "A Y", "A Z"]), Box::new(Copycat)],
+        };
+        let results = tournament.run();
+        assert!(results.matches[0].rounds[1..]
+            .iter()
+            .all(|r| r.result == FightResult::Win));
+    }
+
+    #[test]
+    fn test_random_is_seeded() {
+        let game = Game::classic();
+        let mut random = Random::new(42);
+
+        let first: Vec<Shape> = (0..20).map(|_| random.next(game, &[])).collect();
+        random.reset();
+        let replay: Vec<Shape> = (0..20).map(|_| random.next(game, &[])).collect();
+
+        assert_eq!(first, replay);
+        assert!(game.shapes().all(|s| first.contains(&s)));
+    }
+
+    #[test]
+    fn test_round_robin() {
+        let mut tournament = Tournament {
+            game: Game::classic(),
+            rounds: 100,
+            players: vec![
+                Box::new(Guide::load(Game::classic(), "resource/aoc_2/data.txt").unwrap()),
+                Box::new(FrequencyCounter),
+                Box::new(Copycat),
+                Box::new(Random::new(7)),
+            ],
+        };
+
+        let results = tournament.run();
+        assert_eq!(6, results.matches.len());
+
+        let standings = results.standings();
+        assert_eq!(4, standings.len());
+        assert!(standings.iter().all(|s| s.wins + s.draws + s.losses == 3));
+        assert!(standings.windows(2).all(|w| w[0].points() >= w[1].points()));
+
+        let table = results.to_string();
+        assert!(table.starts_with("Rank | Player"));
+        assert_eq!(5, table.lines().count());
+
+        let log = results.log(Game::classic()).to_string();
+        assert_eq!(6 * (100 + 2), log.lines().count());
+    }
+
+    #[test]
+    fn test_bad_guide() {
+        let lines = ["A X", "B Q"].iter().map(|s| s.to_string());
+        assert!(Guide::parse("bad", Game::classic(), lines).is_err());
+        assert!(Guide::parse("empty", Game::classic(), std::iter::empty()).is_err());
+    }
+
+    #[test]
+    fn test_invalid_line() {
+        let lines = ["A X", "BY"].iter().map(|s| s.to_string());
+
+        match Guide::parse("short", Game::classic(), lines) {
+            Err(e) => assert_eq!("short:2: Expected two columns in \"BY\"", e),
+            Ok(_) => panic!("A single column should be rejected"),
+        }
+    }
+}
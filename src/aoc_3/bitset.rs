@@ -0,0 +1,245 @@
+use std::ops::{BitAnd, BitOr};
+
+use super::Priority;
+
+const NONE: u8 = u8::MAX;
+
+/// Up to 64 distinct items with their priority, each item being one bit of an `ItemSet`
+#[derive(Debug, Clone)]
+pub struct Alphabet {
+    items: Vec<(char, Priority)>,
+    ascii: [u8; 128], //bit of every ascii item, NONE if not in the alphabet
+}
+
+impl Alphabet {
+    pub fn new(items: &[(char, Priority)]) -> Result<Alphabet, String> {
+        if items.len() > 64 {
+            return Err(format!(
+                "An alphabet holds at most 64 items, got {}",
+                items.len()
+            ));
+        }
+
+        let mut ascii = [NONE; 128];
+        for (bit, (c, _)) in items.iter().enumerate() {
+            if items[..bit].iter().any(|(other, _)| other == c) {
+                return Err(format!("Duplicate item {}", c));
+            }
+            if c.is_ascii() {
+                ascii[*c as usize] = bit as u8;
+            }
+        }
+
+        Ok(Alphabet {
+            items: items.to_vec(),
+            ascii,
+        })
+    }
+
+    /// Priorities 1, 2, 3... in the order of `chars`
+    pub fn from_chars(chars: &str) -> Result<Alphabet, String> {
+        let items: Vec<(char, Priority)> = chars.chars().zip(1..).collect();
+        Alphabet::new(&items)
+    }
+
+    /// The puzzle alphabet: a to z are 1 to 26, A to Z are 27 to 52
+    pub fn classic() -> Alphabet {
+        Alphabet::from_chars("abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ")
+            .expect("Classic alphabet is correct")
+    }
+
+    fn bit(&self, c: char) -> Option<u32> {
+        let bit = if c.is_ascii() {
+            self.ascii[c as usize]
+        } else {
+            self.items
+                .iter()
+                .position(|(other, _)| *other == c)
+                .map_or(NONE, |bit| bit as u8)
+        };
+
+        (bit != NONE).then_some(bit as u32)
+    }
+
+    pub fn items(&self, s: &str) -> Result<ItemSet, String> {
+        s.chars().try_fold(ItemSet::default(), |set, c| {
+            self.bit(c)
+                .map(|bit| ItemSet(set.0 | 1 << bit))
+                .ok_or_else(|| format!("Unknown item {}", c))
+        })
+    }
+
+    pub fn priority(&self, c: char) -> Option<Priority> {
+        self.bit(c).map(|bit| self.items[bit as usize].1)
+    }
+
+    /// Sum of the priorities of every item of the set
+    pub fn priorities(&self, set: ItemSet) -> Priority {
+        set.bits().map(|bit| self.items[bit as usize].1).sum()
+    }
+
+    /// Items of the set in alphabet order
+    pub fn chars(&self, set: ItemSet) -> Vec<char> {
+        set.bits().map(|bit| self.items[bit as usize].0).collect()
+    }
+}
+
+/// A set of items of an `Alphabet`, intersection and union are a single instruction
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ItemSet(u64);
+
+impl ItemSet {
+    pub fn len(&self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    fn bits(self) -> impl Iterator<Item = u32> {
+        let mut rest = self.0;
+        std::iter::from_fn(move || {
+            (rest != 0).then(|| {
+                let bit = rest.trailing_zeros();
+                rest &= rest - 1;
+                bit
+            })
+        })
+    }
+}
+
+impl BitAnd for ItemSet {
+    type Output = ItemSet;
+
+    fn bitand(self, rhs: Self) -> Self::Output {
+        ItemSet(self.0 & rhs.0)
+    }
+}
+
+impl BitOr for ItemSet {
+    type Output = ItemSet;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        ItemSet(self.0 | rhs.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use itertools::Itertools;
+    use test::Bencher;
+
+    use super::*;
+    use crate::{
+        aoc_3::{Part1, Part2},
+        get_input,
+        runner::Solution,
+    };
+
+    #[test]
+    fn test_alphabet() {
+        let alphabet = Alphabet::classic();
+
+        assert_eq!(Some(1), alphabet.priority('a'));
+        assert_eq!(Some(26), alphabet.priority('z'));
+        assert_eq!(Some(27), alphabet.priority('A'));
+        assert_eq!(Some(52), alphabet.priority('Z'));
+        assert_eq!(None, alphabet.priority('1'));
+        assert!(alphabet.items("ab1").is_err());
+
+        let custom = Alphabet::new(&[('é', 10), ('1', 20), ('a', 5)]).unwrap();
+        let set = custom.items("a1éa").unwrap();
+        assert_eq!(3, set.len());
+        assert_eq!(35, custom.priorities(set));
+        assert_eq!(vec!['é', '1', 'a'], custom.chars(set));
+
+        assert!(Alphabet::from_chars("abca").is_err());
+        assert!(Alphabet::from_chars(&"x".repeat(65)).is_err());
+    }
+
+    #[test]
+    fn test_item_set() {
+        let alphabet = Alphabet::classic();
+        let first = alphabet.items("vJrwpWtwJgWr").unwrap();
+        let second = alphabet.items("hcsFMMfFFhFp").unwrap();
+
+        let common = first & second;
+        assert_eq!(vec!['p'], alphabet.chars(common));
+        assert_eq!(16, alphabet.priorities(common));
+        assert_eq!(first, first | common);
+        assert!(ItemSet::default().is_empty());
+    }
+
+    /// The former implementation, kept as a reference for the benchmarks.
+    /// Takes its input by value like `Solution::parse`, so both sides pay for the same copy
+    fn hashset_part1(input: Vec<String>) -> Priority {
+        input
+            .into_iter()
+            .map(|line| {
+                let (first, second) = line.split_at(line.len() / 2);
+                let first: HashSet<char> = first.chars().collect();
+                let second: HashSet<char> = second.chars().collect();
+                let common: Vec<char> = first.intersection(&second).cloned().collect();
+                classic_priority(common[0])
+            })
+            .sum()
+    }
+
+    fn hashset_part2(input: Vec<String>) -> Priority {
+        input
+            .into_iter()
+            .map(|line| line.chars().collect::<HashSet<char>>())
+            .tuples()
+            .map(|(elf1, elf2, elf3)| {
+                let common: HashSet<char> = elf1.intersection(&elf2).cloned().collect();
+                let common: Vec<char> = common.intersection(&elf3).cloned().collect();
+                classic_priority(common[0])
+            })
+            .sum()
+    }
+
+    fn classic_priority(c: char) -> Priority {
+        if c.is_ascii_lowercase() {
+            1 + c as u32 - 'a' as u32
+        } else {
+            27 + c as u32 - 'A' as u32
+        }
+    }
+
+    fn input() -> Vec<String> {
+        get_input("resource/aoc_3/data.txt")
+    }
+
+    #[test]
+    fn test_same_as_hashset() {
+        assert_eq!(hashset_part1(input()), Part1.solve(Part1.parse(input())));
+        assert_eq!(hashset_part2(input()), Part2.solve(Part2.parse(input())));
+    }
+
+    #[bench]
+    fn bench_bitset_part1(b: &mut Bencher) {
+        let input = input();
+        b.iter(|| Part1.solve(Part1.parse(input.clone())));
+    }
+
+    #[bench]
+    fn bench_hashset_part1(b: &mut Bencher) {
+        let input = input();
+        b.iter(|| hashset_part1(input.clone()));
+    }
+
+    #[bench]
+    fn bench_bitset_part2(b: &mut Bencher) {
+        let input = input();
+        b.iter(|| Part2.solve(Part2.parse(input.clone())));
+    }
+
+    #[bench]
+    fn bench_hashset_part2(b: &mut Bencher) {
+        let input = input();
+        b.iter(|| hashset_part2(input.clone()));
+    }
+}
@@ -0,0 +1,310 @@
+use std::{fmt::Display, ops::RangeInclusive};
+
+use itertools::Itertools;
+
+use super::{
+    bitset::{Alphabet, ItemSet},
+    Priority,
+};
+
+/// How rucksacks are gathered into groups of `size` elves
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Grouping {
+    /// Consecutive groups, lines 1 to size, then size+1 to 2*size...
+    Fixed(usize),
+    /// Every window of `size` consecutive lines
+    Sliding(usize),
+}
+
+impl Grouping {
+    pub fn size(&self) -> usize {
+        match self {
+            Grouping::Fixed(size) | Grouping::Sliding(size) => *size,
+        }
+    }
+}
+
+/// One line of the input, the whole content and both compartments
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rucksack {
+    pub line: usize,
+    pub first: ItemSet,
+    pub second: ItemSet,
+}
+
+impl Rucksack {
+    #[allow(clippy::manual_is_multiple_of)]
+    pub fn parse(alphabet: &Alphabet, line: usize, s: &str) -> Result<Rucksack, String> {
+        if s.chars().count() % 2 != 0 {
+            return Err(format!("Line {}: odd number of items", line));
+        }
+
+        let mid = s
+            .char_indices()
+            .nth(s.chars().count() / 2)
+            .map_or(0, |(i, _)| i);
+        let (first, second) = s.split_at(mid);
+        let items = |s: &str| {
+            alphabet
+                .items(s)
+                .map_err(|e| format!("Line {}: {}", line, e))
+        };
+
+        Ok(Rucksack {
+            line,
+            first: items(first)?,
+            second: items(second)?,
+        })
+    }
+
+    pub fn items(&self) -> ItemSet {
+        self.first | self.second
+    }
+
+    /// Items found in both compartments, the puzzle expects exactly one
+    pub fn duplicates(&self) -> ItemSet {
+        self.first & self.second
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Group {
+    /// Line numbers of the rucksacks, from 1
+    pub lines: RangeInclusive<usize>,
+    /// Items carried by every elf of the group, the puzzle expects exactly one badge
+    pub common: ItemSet,
+}
+
+impl Group {
+    pub fn badge(&self) -> Option<ItemSet> {
+        (self.common.len() == 1).then_some(self.common)
+    }
+}
+
+/// Groups of rucksacks, a trailing incomplete group in `Fixed` mode is left out.
+/// The size must not be 0, which `Diagnostics::parse` reports as an error
+pub(crate) fn groups(rucksacks: &[Rucksack], grouping: Grouping) -> Vec<Group> {
+    let size = grouping.size();
+
+    let group = |members: &[Rucksack]| Group {
+        lines: members[0].line..=members[members.len() - 1].line,
+        common: members
+            .iter()
+            .map(|r| r.items())
+            .reduce(|a, b| a & b)
+            .unwrap_or_default(),
+    };
+
+    match grouping {
+        Grouping::Fixed(_) => rucksacks
+            .chunks(size)
+            .filter(|c| c.len() == size)
+            .map(group)
+            .collect(),
+        Grouping::Sliding(_) => rucksacks.windows(size).map(group).collect(),
+    }
+}
+
+/// Everything that does not match the puzzle expectations, reported instead of panicking
+pub struct Diagnostics<'a> {
+    pub alphabet: &'a Alphabet,
+    pub grouping: Grouping,
+    pub rucksacks: Vec<Rucksack>,
+    pub groups: Vec<Group>,
+}
+
+impl<'a> Diagnostics<'a> {
+    pub fn parse<I: Iterator<Item = String>>(
+        alphabet: &'a Alphabet,
+        grouping: Grouping,
+        lines: I,
+    ) -> Result<Self, String> {
+        if grouping.size() == 0 {
+            return Err("Groups need at least one elf".to_string());
+        }
+
+        let rucksacks = lines
+            .enumerate()
+            .filter(|(_, line)| !line.is_empty())
+            .map(|(i, line)| Rucksack::parse(alphabet, i + 1, &line))
+            .collect::<Result<Vec<Rucksack>, String>>()?;
+        let groups = groups(&rucksacks, grouping);
+
+        Ok(Diagnostics {
+            alphabet,
+            grouping,
+            rucksacks,
+            groups,
+        })
+    }
+
+    /// Groups without exactly one common item
+    pub fn bad_groups(&self) -> impl Iterator<Item = &Group> {
+        self.groups.iter().filter(|g| g.badge().is_none())
+    }
+
+    /// Rucksacks without exactly one item in both compartments
+    pub fn bad_rucksacks(&self) -> impl Iterator<Item = &Rucksack> {
+        self.rucksacks.iter().filter(|r| r.duplicates().len() != 1)
+    }
+
+    /// Lines not part of any group
+    pub fn ungrouped(&self) -> usize {
+        match self.grouping {
+            Grouping::Fixed(size) => self.rucksacks.len() % size,
+            Grouping::Sliding(_) => 0,
+        }
+    }
+
+    /// Part 2 over the groups with a badge only
+    pub fn badge_priorities(&self) -> Priority {
+        self.groups
+            .iter()
+            .filter_map(|g| g.badge())
+            .map(|badge| self.alphabet.priorities(badge))
+            .sum()
+    }
+
+    fn describe(&self, items: ItemSet) -> String {
+        match items.len() {
+            0 => "no common item".to_string(),
+            n => format!(
+                "{} common item{}: {}",
+                n,
+                if n > 1 { "s" } else { "" },
+                self.alphabet.chars(items).iter().join(", ")
+            ),
+        }
+    }
+}
+
+impl Display for Diagnostics<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Rucksacks: {}", self.rucksacks.len())?;
+        writeln!(f, "Compartment duplicates:")?;
+        for r in self.rucksacks.iter() {
+            let mark = if r.duplicates().len() == 1 { "" } else { " !" };
+            writeln!(
+                f,
+                "  line {}: {}{}",
+                r.line,
+                self.alphabet.chars(r.duplicates()).iter().join(", "),
+                mark
+            )?;
+        }
+
+        let mode = match self.grouping {
+            Grouping::Fixed(_) => "fixed",
+            Grouping::Sliding(_) => "sliding",
+        };
+        writeln!(
+            f,
+            "Groups of {} ({}): {}",
+            self.grouping.size(),
+            mode,
+            self.groups.len()
+        )?;
+        let bad_groups: Vec<&Group> = self.bad_groups().collect();
+        for g in bad_groups.iter() {
+            writeln!(
+                f,
+                "  lines {}-{}: {}",
+                g.lines.start(),
+                g.lines.end(),
+                self.describe(g.common)
+            )?;
+        }
+        if self.ungrouped() > 0 {
+            writeln!(f, "  {} trailing lines not grouped", self.ungrouped())?;
+        }
+
+        writeln!(
+            f,
+            "Badges: {} of {} groups, priorities {}",
+            self.groups.len() - bad_groups.len(),
+            self.groups.len(),
+            self.badge_priorities()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aoc_3::CLASSIC;
+
+    const INPUT: [&str; 6] = [
+        "vJrwpWtwJgWrhcsFMMfFFhFp",
+        "jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL",
+        "PmmdzqPrVvPwwTWBwg",
+        "wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn",
+        "ttgJtRGJQctTZtZT",
+        "CrZsJsPPZsGzwwsLwLmpwMDw",
+    ];
+
+    fn diagnose(grouping: Grouping, lines: &[&str]) -> Diagnostics<'static> {
+        Diagnostics::parse(&CLASSIC, grouping, lines.iter().map(|s| s.to_string())).unwrap()
+    }
+
+    #[test]
+    fn test_fixed_groups() {
+        let diagnostics = diagnose(Grouping::Fixed(3), &INPUT);
+
+        assert_eq!(2, diagnostics.groups.len());
+        assert_eq!(0, diagnostics.bad_groups().count());
+        assert_eq!(18 + 52, diagnostics.badge_priorities());
+        assert_eq!(4..=6, diagnostics.groups[1].lines);
+
+        let pairs = diagnose(Grouping::Fixed(2), &INPUT);
+        assert_eq!(3, pairs.groups.len());
+
+        let fours = diagnose(Grouping::Fixed(4), &INPUT);
+        assert_eq!(1, fours.groups.len());
+        assert_eq!(2, fours.ungrouped());
+    }
+
+    #[test]
+    fn test_sliding_groups() {
+        let diagnostics = diagnose(Grouping::Sliding(3), &INPUT);
+
+        assert_eq!(4, diagnostics.groups.len());
+        assert_eq!(2..=4, diagnostics.groups[1].lines);
+        assert_eq!(0, diagnostics.ungrouped());
+    }
+
+    #[test]
+    fn test_bad_groups() {
+        let lines = ["abab", "cdcd", "aAaA", "aBaB", "aBBa", "BaaB"];
+        let diagnostics = diagnose(Grouping::Fixed(3), &lines);
+
+        let bad: Vec<&Group> = diagnostics.bad_groups().collect();
+        assert_eq!(2, bad.len());
+        assert!(bad[0].common.is_empty());
+        assert_eq!(vec!['a', 'B'], CLASSIC.chars(bad[1].common));
+        assert_eq!(0, diagnostics.badge_priorities());
+
+        let bad: Vec<usize> = diagnostics.bad_rucksacks().map(|r| r.line).collect();
+        assert_eq!(vec![1, 2, 3, 4, 5, 6], bad);
+
+        let report = diagnostics.to_string();
+        assert!(report.contains("  lines 1-3: no common item\n"));
+        assert!(report.contains("  lines 4-6: 2 common items: a, B\n"));
+        assert!(report.contains("  line 3: a, A !\n"));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let odd = ["abc"].iter().map(|s| s.to_string());
+        let err = Diagnostics::parse(&CLASSIC, Grouping::Fixed(3), odd);
+        assert_eq!(Some("Line 1: odd number of items".to_string()), err.err());
+
+        let unknown = ["abab", "a1a1"].iter().map(|s| s.to_string());
+        let err = Diagnostics::parse(&CLASSIC, Grouping::Fixed(3), unknown);
+        assert!(err.err().unwrap().starts_with("Line 2:"));
+
+        for grouping in [Grouping::Fixed(0), Grouping::Sliding(0)] {
+            let err = Diagnostics::parse(&CLASSIC, grouping, INPUT.iter().map(|s| s.to_string()));
+            assert_eq!(Some("Groups need at least one elf".to_string()), err.err());
+        }
+    }
+}
@@ -1,95 +1,113 @@
-use std::collections::HashSet;
+pub mod bitset;
+pub mod diagnostics;
 
-use itertools::Itertools;
+use lazy_static::lazy_static;
 use tuple::Map;
 
-use crate::get_input;
+use crate::{get_input, runner::Solution};
 
-#[derive(PartialEq, Eq, Hash, Clone)]
-struct Item(char);
+use self::{
+    bitset::{Alphabet, ItemSet},
+    diagnostics::{groups, Grouping, Rucksack},
+};
 
 type Priority = u32;
 
-impl Item {
-    fn priority(&self) -> Priority {
-        let code = self.0 as u32;
+const GROUP_SIZE: usize = 3;
 
-        if self.0.is_ascii_lowercase() {
-            let code_a = 'a' as u32;
+lazy_static! {
+    static ref CLASSIC: Alphabet = Alphabet::classic();
+}
 
-            1 + code - code_a
-        } else if self.0.is_ascii_uppercase() {
-            let code_uppera = 'A' as u32;
+pub(crate) struct Part1;
+pub(crate) struct Part2;
 
-            27 + code - code_uppera
-        } else {
-            panic!("Non ascii in input {}", self.0)
-        }
-    }
-}
+impl Solution for Part1 {
+    type Parsed = Vec<(ItemSet, ItemSet)>;
+    type Output = Priority;
 
-pub fn aoc_3_1() {
-    let input = get_input("resource/aoc_3/data.txt");
+    fn parse(&self, input: Vec<String>) -> Self::Parsed {
+        input
+            .iter()
+            .map(|line| {
+                assert!(line.len() % 2 == 0);
 
-    let mut result = 0;
+                let mid = line.len() / 2;
 
-    for line in input.iter() {
-        assert!(line.len() % 2 == 0);
+                line.split_at(mid).map(to_items)
+            })
+            .collect()
+    }
 
-        let mid = line.len() / 2;
+    fn solve(&self, parsed: Self::Parsed) -> Self::Output {
+        let mut result = 0;
 
-        let (first_half, second_half) = line.split_at(mid).map(to_items);
+        for (first_half, second_half) in parsed.into_iter() {
+            let common_items = first_half & second_half;
 
-        let common_items = first_half
-            .intersection(&second_half)
-            .map(|el| el.clone())
-            .collect::<Vec<Item>>();
+            assert!(common_items.len() == 1);
 
-        assert!(common_items.len() == 1);
+            result += CLASSIC.priorities(common_items);
+        }
 
-        result += common_items[0].priority();
+        result
     }
-
-    println!("ACO-3-1 Sum of priorities {}", &result);
 }
 
-pub fn aoc_3_2() {
-    let input = get_input("resource/aoc_3/data.txt");
+impl Solution for Part2 {
+    type Parsed = Vec<Rucksack>;
+    type Output = Priority;
+
+    #[allow(clippy::manual_is_multiple_of)]
+    fn parse(&self, input: Vec<String>) -> Self::Parsed {
+        assert!(input.len() % GROUP_SIZE == 0);
 
-    assert!(input.len() % 3 == 0);
+        input
+            .iter()
+            .enumerate()
+            .map(|(i, line)| {
+                Rucksack::parse(&CLASSIC, i + 1, line).unwrap_or_else(|e| panic!("{}", e))
+            })
+            .collect()
+    }
 
-    let mut iter = input.iter();
+    fn solve(&self, parsed: Self::Parsed) -> Self::Output {
+        groups(&parsed, Grouping::Fixed(GROUP_SIZE))
+            .iter()
+            .map(|group| {
+                let badge = group.badge().unwrap_or_else(|| {
+                    panic!(
+                        "Lines {:?}: expect one common item, run `aoc stats 3` for details",
+                        group.lines
+                    )
+                });
+
+                CLASSIC.priorities(badge)
+            })
+            .sum()
+    }
+}
 
-    let mut result = 0;
+pub fn aoc_3_1() {
+    let input = get_input("resource/aoc_3/data.txt");
 
-    while let Some(three_elves) = iter.next_tuple::<(&String, &String, &String)>() {
-        let (elf1, elf2, elf3) = three_elves.map(to_items);
+    let result = Part1.solve(Part1.parse(input));
 
-        let common_items: HashSet<Item> = elf1
-            .intersection(&elf2)
-            .map(|el| el.clone())
-            .collect::<HashSet<Item>>()
-            .intersection(&elf3)
-            .map(|el| el.clone())
-            .collect();
+    println!("ACO-3-1 Sum of priorities {}", &result);
+}
 
-        assert!(common_items.len() == 1);
+pub fn aoc_3_2() {
+    let input = get_input("resource/aoc_3/data.txt");
 
-        result += common_items
-            .iter()
-            .next()
-            .expect("No common items")
-            .priority();
-    }
+    let result = Part2.solve(Part2.parse(input));
 
     println!("AOC-3-2 Sum of priorities for 3 elves {}", &result);
 }
 
-fn to_items<T: AsRef<str>>(s: T) -> HashSet<Item> {
-    s.as_ref()
-        .chars()
-        .map(|c| Item(c))
-        .collect::<HashSet<Item>>()
+fn to_items<T: AsRef<str>>(s: T) -> ItemSet {
+    CLASSIC
+        .items(s.as_ref())
+        .unwrap_or_else(|e| panic!("{} in input", e))
 }
 
 #[cfg(test)]
@@ -98,16 +116,12 @@ mod tests {
 
     #[test]
     fn test_priority() {
-        let a = Item('a');
-        let z = Item('z');
-
-        let upper_a = Item('A');
-        let upper_z = Item('Z');
+        let priority = |s: &str| CLASSIC.priorities(to_items(s));
 
-        assert_eq!(a.priority(), 1);
-        assert_eq!(z.priority(), 26);
-        assert_eq!(upper_a.priority(), 27);
-        assert_eq!(upper_z.priority(), 52);
+        assert_eq!(priority("a"), 1);
+        assert_eq!(priority("z"), 26);
+        assert_eq!(priority("A"), 27);
+        assert_eq!(priority("Z"), 52);
     }
 
     #[test]
@@ -0,0 +1,243 @@
+use std::fmt::Display;
+
+use super::Interval;
+
+/// Static interval tree: intervals sorted by start, seen as an implicit balanced binary
+/// tree where each node knows the highest end of its subtree.
+/// A query visits O(min(n, k log n)) nodes for k results.
+pub struct IntervalTree {
+    /// (interval, position in the input), sorted on interval start
+    nodes: Vec<(Interval, usize)>,
+    /// Highest end of the subtree rooted at the same position
+    max_end: Vec<u32>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Query {
+    /// Intervals holding the section
+    Stab(u32),
+    /// Intervals sharing at least one section with the range
+    Overlaps(Interval),
+    /// Intervals holding the whole range
+    Containing(Interval),
+    /// Intervals inside the range
+    Within(Interval),
+}
+
+impl TryFrom<&str> for Query {
+    type Error = String;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let (kind, argument) = value
+            .trim()
+            .split_once(' ')
+            .ok_or(format!("Query format is not correct {}", value))?;
+        let range = || Interval::try_from(argument.trim());
+
+        match kind {
+            "stab" => argument
+                .trim()
+                .parse()
+                .map(Query::Stab)
+                .map_err(|_| format!("Expect a section get {}", argument)),
+            "overlaps" => range().map(Query::Overlaps),
+            "containing" => range().map(Query::Containing),
+            "within" => range().map(Query::Within),
+            other => Err(format!("Unknown query {}", other)),
+        }
+    }
+}
+
+impl Display for Query {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Query::Stab(section) => write!(f, "stab {}", section),
+            Query::Overlaps(range) => write!(f, "overlaps {}", range),
+            Query::Containing(range) => write!(f, "containing {}", range),
+            Query::Within(range) => write!(f, "within {}", range),
+        }
+    }
+}
+
+impl IntervalTree {
+    pub fn new<I: IntoIterator<Item = Interval>>(intervals: I) -> Self {
+        let mut nodes: Vec<(Interval, usize)> = intervals
+            .into_iter()
+            .enumerate()
+            .map(|(i, interval)| (interval, i))
+            .collect();
+        nodes.sort_by_key(|(interval, _)| (interval.min, interval.max));
+
+        let mut max_end = vec![0; nodes.len()];
+        fill_max_end(&nodes, &mut max_end, 0, nodes.len());
+
+        IntervalTree { nodes, max_end }
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Positions in the input of the matching intervals, in input order
+    pub fn query(&self, query: &Query) -> Vec<usize> {
+        let mut found = match *query {
+            Query::Stab(section) => self.search(section, section, |_| true),
+            Query::Overlaps(range) => self.search(range.min, range.max, |_| true),
+            Query::Containing(range) => self.search(range.max, range.min, |_| true),
+            Query::Within(range) => self.search(range.min, range.max, |i| range.contains(i)),
+        };
+        found.sort_unstable();
+        found
+    }
+
+    pub fn stab(&self, section: u32) -> Vec<usize> {
+        self.query(&Query::Stab(section))
+    }
+
+    pub fn overlapping(&self, range: Interval) -> Vec<usize> {
+        self.query(&Query::Overlaps(range))
+    }
+
+    pub fn containing(&self, range: Interval) -> Vec<usize> {
+        self.query(&Query::Containing(range))
+    }
+
+    pub fn within(&self, range: Interval) -> Vec<usize> {
+        self.query(&Query::Within(range))
+    }
+
+    /// Intervals ending at or after `end_from` and starting at or before `start_to`
+    fn search<F: Fn(&Interval) -> bool>(
+        &self,
+        end_from: u32,
+        start_to: u32,
+        filter: F,
+    ) -> Vec<usize> {
+        let mut found = vec![];
+        let mut stack = vec![(0, self.nodes.len())];
+
+        while let Some((lo, hi)) = stack.pop() {
+            if lo >= hi {
+                continue;
+            }
+            let mid = (lo + hi) / 2;
+            if self.max_end[mid] < end_from {
+                continue; //the whole subtree ends too early
+            }
+
+            stack.push((lo, mid));
+
+            let (interval, position) = &self.nodes[mid];
+            if interval.min <= start_to {
+                if interval.max >= end_from && filter(interval) {
+                    found.push(*position);
+                }
+                stack.push((mid + 1, hi));
+            } //else this node and the right subtree start too late
+        }
+
+        found
+    }
+}
+
+fn fill_max_end(nodes: &[(Interval, usize)], max_end: &mut [u32], lo: usize, hi: usize) -> u32 {
+    if lo >= hi {
+        return 0;
+    }
+
+    let mid = (lo + hi) / 2;
+    let left = fill_max_end(nodes, max_end, lo, mid);
+    let right = fill_max_end(nodes, max_end, mid + 1, hi);
+    max_end[mid] = nodes[mid].0.max.max(left).max(right);
+    max_end[mid]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn interval(s: &str) -> Interval {
+        s.try_into().unwrap()
+    }
+
+    fn tree(intervals: &[&str]) -> IntervalTree {
+        IntervalTree::new(intervals.iter().map(|s| interval(s)))
+    }
+
+    const INTERVALS: [&str; 8] = ["2-4", "6-8", "2-3", "4-5", "5-7", "7-9", "2-8", "3-7"];
+
+    #[test]
+    fn test_queries() {
+        let tree = tree(&INTERVALS);
+
+        assert_eq!(8, tree.len());
+        assert_eq!(vec![0, 3, 6, 7], tree.stab(4));
+        assert_eq!(vec![1, 5, 6], tree.stab(8));
+        assert!(tree.stab(10).is_empty());
+        assert_eq!(vec![1, 4, 5, 6, 7], tree.overlapping(interval("7-12")));
+        assert_eq!(vec![6, 7], tree.containing(interval("3-6")));
+        assert_eq!(vec![1, 3, 4], tree.within(interval("4-8")));
+    }
+
+    #[test]
+    fn test_against_scan() {
+        //pseudo random intervals, checked against a linear scan
+        let mut seed: u64 = 12345;
+        let mut next = |bound: u64| {
+            seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1);
+            ((seed >> 33) % bound) as u32
+        };
+        let intervals: Vec<Interval> = (0..500)
+            .map(|_| {
+                let min = next(1000);
+                Interval {
+                    min,
+                    max: min + next(50),
+                }
+            })
+            .collect();
+        let tree = IntervalTree::new(intervals.iter().copied());
+
+        for _ in 0..200 {
+            let min = next(1100);
+            let range = Interval {
+                min,
+                max: min + next(30),
+            };
+            let scan = |f: &dyn Fn(&Interval) -> bool| -> Vec<usize> {
+                (0..intervals.len()).filter(|i| f(&intervals[*i])).collect()
+            };
+
+            assert_eq!(scan(&|i| i.contains_elem(range.min)), tree.stab(range.min));
+            assert_eq!(scan(&|i| i.overlaps(&range)), tree.overlapping(range));
+            assert_eq!(scan(&|i| i.contains(&range)), tree.containing(range));
+            assert_eq!(scan(&|i| range.contains(i)), tree.within(range));
+        }
+    }
+
+    #[test]
+    fn test_parse_query() {
+        assert_eq!(Ok(Query::Stab(5)), Query::try_from("stab 5"));
+        assert_eq!(
+            Ok(Query::Overlaps(interval("3-7"))),
+            Query::try_from("overlaps 3-7")
+        );
+        assert_eq!(
+            Ok(Query::Within(interval("1-2"))),
+            Query::try_from(" within 1-2 ")
+        );
+        assert!(Query::try_from("stab x").is_err());
+        assert!(Query::try_from("nearest 3-4").is_err());
+        assert!(Query::try_from("stab").is_err());
+        assert!(Query::try_from("overlaps 1-99999999999").is_err());
+        assert!(Query::try_from("within 5-3").is_err());
+        assert_eq!(
+            "containing 3-7",
+            Query::Containing(interval("3-7")).to_string()
+        );
+    }
+}
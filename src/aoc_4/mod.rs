@@ -1,38 +1,66 @@
+pub mod index;
+pub mod population;
+
+use std::fmt::Display;
+
 use itertools::Itertools;
 use lazy_static::lazy_static;
 use regex::Regex;
 use tuple::Map;
 
-use crate::get_input;
+use crate::{get_input, runner::Solution};
 
-pub fn aoc_4_1() {
-    let input = get_input("resource/aoc_4/data.txt");
+pub(crate) struct Part1;
+pub(crate) struct Part2;
 
-    let mut result = 0;
+impl Solution for Part1 {
+    type Parsed = Vec<(Interval, Interval)>;
+    type Output = usize;
 
-    for line in input.iter() {
-        let (interval_1, interval_2) = to_intervals(line);
+    fn parse(&self, input: Vec<String>) -> Self::Parsed {
+        input.iter().map(to_intervals).collect()
+    }
 
-        if interval_1.contains(&interval_2) || interval_2.contains(&interval_1) {
-            result += 1;
-        }
+    fn solve(&self, parsed: Self::Parsed) -> Self::Output {
+        parsed
+            .iter()
+            .filter(|(interval_1, interval_2)| {
+                interval_1.contains(interval_2) || interval_2.contains(interval_1)
+            })
+            .count()
+    }
+}
+
+impl Solution for Part2 {
+    type Parsed = Vec<(Interval, Interval)>;
+    type Output = usize;
+
+    fn parse(&self, input: Vec<String>) -> Self::Parsed {
+        input.iter().map(to_intervals).collect()
     }
 
-    println!("AOC-4-1 Number of full overlaps {}", &result);
+    fn solve(&self, parsed: Self::Parsed) -> Self::Output {
+        parsed
+            .iter()
+            .filter(|(interval_1, interval_2)| {
+                interval_1.overlaps(interval_2) || interval_2.overlaps(interval_1)
+            })
+            .count()
+    }
 }
 
-pub fn aoc_4_2() {
+pub fn aoc_4_1() {
     let input = get_input("resource/aoc_4/data.txt");
 
-    let mut result = 0;
+    let result = Part1.solve(Part1.parse(input));
 
-    for line in input.iter() {
-        let (interval_1, interval_2) = to_intervals(line);
+    println!("AOC-4-1 Number of full overlaps {}", &result);
+}
 
-        if interval_1.overlaps(&interval_2) || interval_2.overlaps(&interval_1) {
-            result += 1;
-        }
-    }
+pub fn aoc_4_2() {
+    let input = get_input("resource/aoc_4/data.txt");
+
+    let result = Part2.solve(Part2.parse(input));
 
     println!("AOC-4-2 Number of overlaps {}", &result);
 }
@@ -46,7 +74,17 @@ fn to_intervals(input: &String) -> (Interval, Interval) {
         .map(|s| TryInto::<Interval>::try_into(s).unwrap()) //convert each element in the group of 2 to an interval
 }
 
-struct Interval {
+/// Same as `to_intervals`, reporting malformed lines instead of panicking
+fn try_to_intervals(input: &str) -> Result<(Interval, Interval), String> {
+    let (first, second) = input
+        .split_once(',')
+        .ok_or(format!("Expect two assignments in {}", input))?;
+
+    Ok((first.try_into()?, second.try_into()?))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Interval {
     min: u32,
     max: u32,
 }
@@ -68,23 +106,36 @@ impl Interval {
     }
 }
 
+impl Display for Interval {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}-{}", self.min, self.max)
+    }
+}
+
 impl TryFrom<&str> for Interval {
     type Error = String; //just sends an error message because I'm lazy
 
     fn try_from(value: &str) -> Result<Self, Self::Error> {
         lazy_static! {
-            static ref INTERVAL_REGEX: Regex = Regex::new(r"(?P<min>[0-9]+)-(?P<max>[0-9]+)")
+            static ref INTERVAL_REGEX: Regex = Regex::new(r"^(?P<min>[0-9]+)-(?P<max>[0-9]+)$")
                 .expect("Interval regex should be correct");
         }
 
-        if let Some(captures) = INTERVAL_REGEX.captures(value) {
-            Ok(Interval {
-                min: captures["min"].parse().unwrap(),
-                max: captures["max"].parse().unwrap(),
-            })
-        } else {
-            Err(format!("Interval format is not correct {}", value))
+        let captures = INTERVAL_REGEX
+            .captures(value)
+            .ok_or(format!("Interval format is not correct {}", value))?;
+        let bound = |name: &str| {
+            captures[name]
+                .parse::<u32>()
+                .map_err(|_| format!("Section out of range in {}", value))
+        };
+
+        let (min, max) = (bound("min")?, bound("max")?);
+        if min > max {
+            return Err(format!("Interval is reversed {}", value));
         }
+
+        Ok(Interval { min, max })
     }
 }
 
@@ -147,6 +198,15 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_invalid_interval() {
+        assert!(Interval::try_from("5-3").is_err());
+        assert!(Interval::try_from("1-99999999999").is_err());
+        assert!(Interval::try_from("x1-3").is_err());
+        assert!(Interval::try_from("1-3,").is_err());
+        assert_eq!(Ok(Interval { min: 3, max: 3 }), Interval::try_from("3-3"));
+    }
+
     #[test]
     fn test_aoc_4_1() {
         aoc_4_1();
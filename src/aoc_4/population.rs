@@ -0,0 +1,320 @@
+use std::fmt::Display;
+
+use itertools::Itertools;
+
+use super::{try_to_intervals, Interval};
+
+/// One elf of the population, two per input line
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Assignment {
+    /// From 1, in reading order
+    pub elf: usize,
+    /// From 1
+    pub line: usize,
+    pub interval: Interval,
+}
+
+/// Every assignment of the input as a whole, analysed with a sweep line over the
+/// interval bounds so every question is answered in O(n log n)
+pub struct Population {
+    assignments: Vec<Assignment>,
+    /// Piecewise constant coverage, consecutive segments from the lowest to the highest section
+    coverage: Vec<(Interval, usize)>,
+}
+
+impl Population {
+    pub fn parse<I: Iterator<Item = String>>(lines: I) -> Result<Self, String> {
+        let mut intervals = vec![];
+        for (i, line) in lines.enumerate().filter(|(_, line)| !line.is_empty()) {
+            let (first, second) =
+                try_to_intervals(&line).map_err(|e| format!("Line {}: {}", i + 1, e))?;
+            intervals.extend([(i + 1, first), (i + 1, second)]);
+        }
+
+        Ok(Population::new(intervals.into_iter()))
+    }
+
+    /// From (line, interval) pairs
+    pub fn new<I: Iterator<Item = (usize, Interval)>>(intervals: I) -> Self {
+        let assignments: Vec<Assignment> = intervals
+            .enumerate()
+            .map(|(i, (line, interval))| Assignment {
+                elf: i + 1,
+                line,
+                interval,
+            })
+            .collect();
+        let coverage = sweep(&assignments);
+
+        Population {
+            assignments,
+            coverage,
+        }
+    }
+
+    pub fn assignments(&self) -> &[Assignment] {
+        &self.assignments
+    }
+
+    /// Segments of sections between the lowest and highest assigned section,
+    /// with the number of elves covering each section of the segment
+    pub fn coverage(&self) -> &[(Interval, usize)] {
+        &self.coverage
+    }
+
+    /// Number of elves on the most covered sections, and those sections
+    pub fn max_coverage(&self) -> Option<(usize, Vec<Interval>)> {
+        let max = self.coverage.iter().map(|(_, count)| *count).max()?;
+        let sections = self
+            .coverage
+            .iter()
+            .filter(|(_, count)| *count == max)
+            .map(|(interval, _)| *interval)
+            .collect();
+
+        Some((max, sections))
+    }
+
+    /// Sections between the lowest and highest assigned section no elf covers
+    pub fn gaps(&self) -> Vec<Interval> {
+        self.coverage
+            .iter()
+            .filter(|(_, count)| *count == 0)
+            .map(|(interval, _)| *interval)
+            .collect()
+    }
+
+    /// Largest set of elves sharing a section. Intervals pairwise overlapping always share
+    /// a section, so it is the set of elves on the first most covered section.
+    pub fn max_clique(&self) -> Vec<&Assignment> {
+        match self.max_coverage() {
+            None => vec![],
+            Some((_, sections)) => {
+                let section = sections[0].min;
+                self.assignments
+                    .iter()
+                    .filter(|a| a.interval.contains_elem(section))
+                    .collect()
+            }
+        }
+    }
+
+    /// Elves whose every section is covered by at least one other elf, i.e. no section of
+    /// theirs is covered once. Identical assignments are redundant with each other.
+    pub fn redundant(&self) -> Vec<&Assignment> {
+        //single_before[i] is the number of segments covered once before segment i
+        let single_before: Vec<usize> = std::iter::once(0)
+            .chain(self.coverage.iter().scan(0, |count, (_, elves)| {
+                *count += (*elves == 1) as usize;
+                Some(*count)
+            }))
+            .collect();
+        let segment = |section: u32| {
+            self.coverage
+                .partition_point(|(interval, _)| interval.max < section)
+        };
+
+        self.assignments
+            .iter()
+            .filter(|a| {
+                let (first, last) = (segment(a.interval.min), segment(a.interval.max));
+                single_before[last + 1] == single_before[first]
+            })
+            .collect()
+    }
+}
+
+/// Coverage segments from the +1/-1 events at each interval bound,
+/// neighbouring segments with the same coverage are merged
+#[allow(clippy::unnecessary_map_or)]
+fn sweep(assignments: &[Assignment]) -> Vec<(Interval, usize)> {
+    let mut events: Vec<(u64, i64)> = assignments
+        .iter()
+        .flat_map(|a| [(a.interval.min as u64, 1), (a.interval.max as u64 + 1, -1)])
+        .collect();
+    events.sort_unstable();
+
+    //each event position starts a segment running until the next event position
+    let mut starts: Vec<(u64, usize)> = vec![];
+    let mut count: i64 = 0;
+    for (position, events) in &events.into_iter().group_by(|(position, _)| *position) {
+        count += events.map(|(_, delta)| delta).sum::<i64>();
+        if starts
+            .last()
+            .map_or(true, |(_, last)| *last != count as usize)
+        {
+            starts.push((position, count as usize));
+        }
+    }
+
+    starts
+        .iter()
+        .tuple_windows()
+        .map(|((start, count), (next, _))| {
+            let interval = Interval {
+                min: *start as u32,
+                max: (*next - 1) as u32,
+            };
+            (interval, *count)
+        })
+        .collect()
+}
+
+/// Report printed by `aoc stats 4`
+pub struct Analysis<'a>(pub &'a Population);
+
+/// At most `LISTED` elves are listed in the report
+const LISTED: usize = 10;
+
+fn list_elves(elves: &[&Assignment]) -> String {
+    let mut listed = elves
+        .iter()
+        .take(LISTED)
+        .map(|a| format!("#{} ({})", a.elf, a.interval))
+        .join(", ");
+    if elves.len() > LISTED {
+        listed += &format!(" and {} more", elves.len() - LISTED);
+    }
+    listed
+}
+
+impl Display for Analysis<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let population = self.0;
+        writeln!(f, "Elves: {}", population.assignments().len())?;
+
+        match population.max_coverage() {
+            None => return Ok(()),
+            Some((max, sections)) => writeln!(
+                f,
+                "Most covered: {} elves on {}",
+                max,
+                sections.iter().join(", ")
+            )?,
+        }
+
+        let clique = population.max_clique();
+        writeln!(f, "Max clique: {}", list_elves(&clique))?;
+
+        let redundant = population.redundant();
+        writeln!(
+            f,
+            "Redundant elves: {}{}",
+            redundant.len(),
+            if redundant.is_empty() {
+                String::new()
+            } else {
+                format!(", {}", list_elves(&redundant))
+            }
+        )?;
+
+        let gaps = population.gaps();
+        if gaps.is_empty() {
+            writeln!(f, "Gaps: none")
+        } else {
+            writeln!(f, "Gaps: {}", gaps.iter().join(", "))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn population(lines: &[&str]) -> Population {
+        Population::parse(lines.iter().map(|s| s.to_string())).unwrap()
+    }
+
+    fn interval(s: &str) -> Interval {
+        s.try_into().unwrap()
+    }
+
+    const INPUT: [&str; 6] = [
+        "2-4,6-8", "2-3,4-5", "5-7,7-9", "2-8,3-7", "6-6,4-6", "2-6,4-8",
+    ];
+
+    #[test]
+    fn test_coverage() {
+        let population = population(&["1-3,2-5", "8-9,9-9"]);
+
+        let coverage: Vec<(String, usize)> = population
+            .coverage()
+            .iter()
+            .map(|(i, c)| (i.to_string(), *c))
+            .collect();
+        let expected = [
+            ("1-1", 1),
+            ("2-3", 2),
+            ("4-5", 1),
+            ("6-7", 0),
+            ("8-8", 1),
+            ("9-9", 2),
+        ];
+        itertools::assert_equal(coverage, expected.iter().map(|(i, c)| (i.to_string(), *c)));
+
+        assert_eq!(vec![interval("6-7")], population.gaps());
+    }
+
+    #[test]
+    fn test_max_coverage() {
+        let population = population(&INPUT);
+
+        let (max, sections) = population.max_coverage().unwrap();
+        assert_eq!(8, max);
+        assert_eq!(vec![interval("6-6")], sections);
+
+        let clique: Vec<usize> = population.max_clique().iter().map(|a| a.elf).collect();
+        assert_eq!(vec![2, 5, 7, 8, 9, 10, 11, 12], clique);
+    }
+
+    #[test]
+    fn test_blank_lines() {
+        let population = population(&["1-2,3-4", "", "5-6,7-8"]);
+
+        let lines: Vec<(usize, usize)> = population
+            .assignments()
+            .iter()
+            .map(|a| (a.elf, a.line))
+            .collect();
+        assert_eq!(vec![(1, 1), (2, 1), (3, 3), (4, 3)], lines);
+    }
+
+    #[test]
+    fn test_invalid_lines() {
+        let parse = |lines: &[&str]| Population::parse(lines.iter().map(|s| s.to_string()));
+
+        assert_eq!(
+            Some("Line 2: Interval is reversed 5-3".to_string()),
+            parse(&["1-2,3-4", "5-3,1-1"]).err()
+        );
+        assert_eq!(
+            Some("Line 1: Section out of range in 1-99999999999".to_string()),
+            parse(&["1-99999999999,1-1"]).err()
+        );
+        assert!(parse(&["1-2"]).is_err());
+    }
+
+    #[test]
+    fn test_redundant() {
+        let population = population(&["1-5,4-8", "2-3,5-5", "10-12,10-12"]);
+
+        let redundant: Vec<usize> = population.redundant().iter().map(|a| a.elf).collect();
+        assert_eq!(vec![3, 4, 5, 6], redundant);
+    }
+
+    #[test]
+    fn test_report() {
+        let population = population(&["1-5,4-8", "2-3,5-5", "10-12,10-12"]);
+        let report = Analysis(&population).to_string();
+
+        let expected = [
+            "Elves: 6",
+            "Most covered: 3 elves on 5-5",
+            "Max clique: #1 (1-5), #2 (4-8), #4 (5-5)",
+            "Redundant elves: 4, #3 (2-3), #4 (5-5), #5 (10-12), #6 (10-12)",
+            "Gaps: 9-9\n",
+        ]
+        .join("\n");
+        assert_eq!(expected, report);
+    }
+}
@@ -0,0 +1,233 @@
+use std::{collections::VecDeque, fmt::Display, mem};
+
+use super::{draw_crates, Crate, Crates, Move};
+
+/// How a crane carries `nb` crates from the top of a stack to the top of another
+pub trait Crane {
+    fn name(&self) -> String;
+
+    /// Both stacks are distinct and `from` holds at least `nb` crates
+    fn lift(&self, from: &mut VecDeque<Crate>, to: &mut VecDeque<Crate>, nb: usize);
+}
+
+/// One crate at a time, so the moved crates end up in reverse order
+pub struct CrateMover9000;
+
+/// All the crates in one lift, so their order is kept
+pub struct CrateMover9001;
+
+/// At most `capacity` crates per lift, the order being kept within a lift
+pub struct Limited {
+    pub capacity: usize,
+}
+
+impl Crane for CrateMover9000 {
+    fn name(&self) -> String {
+        "CrateMover 9000".to_string()
+    }
+
+    fn lift(&self, from: &mut VecDeque<Crate>, to: &mut VecDeque<Crate>, nb: usize) {
+        Limited { capacity: 1 }.lift(from, to, nb)
+    }
+}
+
+impl Crane for CrateMover9001 {
+    fn name(&self) -> String {
+        "CrateMover 9001".to_string()
+    }
+
+    fn lift(&self, from: &mut VecDeque<Crate>, to: &mut VecDeque<Crate>, nb: usize) {
+        to.extend(from.drain(from.len() - nb..));
+    }
+}
+
+impl Crane for Limited {
+    fn name(&self) -> String {
+        format!("crane lifting {} crates", self.capacity)
+    }
+
+    fn lift(&self, from: &mut VecDeque<Crate>, to: &mut VecDeque<Crate>, nb: usize) {
+        assert!(self.capacity > 0, "A crane lifts at least one crate");
+
+        let mut remaining = nb;
+        while remaining > 0 {
+            let lifted = remaining.min(self.capacity);
+            to.extend(from.drain(from.len() - lifted..));
+            remaining -= lifted;
+        }
+    }
+}
+
+/// `9000`, `9001`, or the number of crates lifted at once by a `Limited` crane
+pub fn by_name(name: &str) -> Result<Box<dyn Crane>, String> {
+    match name {
+        "9000" => Ok(Box::new(CrateMover9000)),
+        "9001" => Ok(Box::new(CrateMover9001)),
+        capacity => match capacity.parse() {
+            Ok(capacity) if capacity > 0 => Ok(Box::new(Limited { capacity })),
+            _ => Err(format!("Unknown crane {}", name)),
+        },
+    }
+}
+
+/// A move the crane cannot do, with the stacks as they were before it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MoveError {
+    /// From 1, in the move list
+    pub move_number: usize,
+    pub message: String,
+    pub drawing: Vec<String>,
+}
+
+impl Display for MoveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Move {}: {}", self.move_number, self.message)?;
+        for line in self.drawing.iter() {
+            writeln!(f, "{}", line)?;
+        }
+        Ok(())
+    }
+}
+
+fn check(crates: &Crates, m: &Move) -> Result<(), String> {
+    for stack in [m.from, m.to] {
+        if stack == 0 || stack > crates.len() {
+            return Err(format!(
+                "{}: no stack {}, there are {} stacks",
+                m,
+                stack,
+                crates.len()
+            ));
+        }
+    }
+
+    if m.from == m.to {
+        return Err(format!("{}: cannot move a stack onto itself", m));
+    }
+
+    let available = crates[m.from - 1].len();
+    if m.nb > available {
+        return Err(format!(
+            "{}: stack {} holds only {} crates",
+            m, m.from, available
+        ));
+    }
+
+    Ok(())
+}
+
+/// Does the move if it is valid, the crates are left untouched otherwise
+pub fn apply<C: Crane + ?Sized>(
+    crane: &C,
+    crates: &mut Crates,
+    move_number: usize,
+    m: &Move,
+) -> Result<(), MoveError> {
+    check(crates, m).map_err(|message| MoveError {
+        move_number,
+        message,
+        drawing: draw_crates(crates),
+    })?;
+
+    let mut from = mem::take(&mut crates[m.from - 1]);
+    crane.lift(&mut from, &mut crates[m.to - 1], m.nb);
+    crates[m.from - 1] = from;
+
+    Ok(())
+}
+
+/// Every move in order, stopping at the first invalid one
+pub fn run<C: Crane + ?Sized>(
+    crane: &C,
+    mut crates: Crates,
+    moves: &[Move],
+) -> Result<Crates, MoveError> {
+    for (i, m) in moves.iter().enumerate() {
+        apply(crane, &mut crates, i + 1, m)?;
+    }
+
+    Ok(crates)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aoc_5::{parse, top_code};
+
+    const INPUT: [&str; 9] = [
+        "    [D]    ",
+        "[N] [C]    ",
+        "[Z] [M] [P]",
+        " 1   2   3 ",
+        "",
+        "move 1 from 2 to 1",
+        "move 3 from 1 to 3",
+        "move 2 from 2 to 1",
+        "move 1 from 1 to 2",
+    ];
+
+    fn input(lines: &[&str]) -> (Crates, Vec<Move>) {
+        let lines: Vec<String> = lines.iter().map(|s| s.to_string()).collect();
+        parse(&mut lines.iter())
+    }
+
+    #[test]
+    fn test_models() {
+        let (crates, moves) = input(&INPUT);
+
+        let crane_9000 = run(&CrateMover9000, crates.clone(), &moves).unwrap();
+        assert_eq!("CMZ", top_code(&crane_9000));
+
+        let crane_9001 = run(&CrateMover9001, crates.clone(), &moves).unwrap();
+        assert_eq!("MCD", top_code(&crane_9001));
+
+        let one = run(&Limited { capacity: 1 }, crates.clone(), &moves).unwrap();
+        assert_eq!(crane_9000, one);
+        let big = run(&Limited { capacity: 3 }, crates.clone(), &moves).unwrap();
+        assert_eq!(crane_9001, big);
+
+        assert_eq!("CrateMover 9001", by_name("9001").unwrap().name());
+        assert_eq!("crane lifting 4 crates", by_name("4").unwrap().name());
+        assert!(by_name("0").is_err());
+    }
+
+    #[test]
+    fn test_limited() {
+        let (mut crates, _) = input(&[
+            "[E]    ", "[D]    ", "[C]    ", "[B]    ", "[A]    ", " 1   2 ",
+        ]);
+
+        apply(
+            &Limited { capacity: 2 },
+            &mut crates,
+            1,
+            &Move::new(5, 1, 2),
+        )
+        .unwrap();
+
+        //lifts DE, then BC, then A
+        itertools::assert_equal(
+            crates[1].iter().map(|c| c.to_string()),
+            vec!["D", "E", "B", "C", "A"],
+        );
+    }
+
+    #[test]
+    fn test_invalid_moves() {
+        let (crates, _) = input(&INPUT);
+        let moves = vec![Move::new(1, 2, 1), Move::new(4, 1, 3)];
+
+        let error = run(&CrateMover9001, crates.clone(), &moves).unwrap_err();
+        assert_eq!(2, error.move_number);
+        assert_eq!(
+            "Move 2: move 4 from 1 to 3: stack 1 holds only 3 crates\n[D]        \n[N] [C]    \n[Z] [M] [P]\n 1   2   3 \n",
+            error.to_string()
+        );
+
+        let mut unchanged = crates.clone();
+        assert!(apply(&CrateMover9000, &mut unchanged, 1, &Move::new(1, 4, 1)).is_err());
+        assert!(apply(&CrateMover9000, &mut unchanged, 1, &Move::new(1, 0, 1)).is_err());
+        assert!(apply(&CrateMover9000, &mut unchanged, 1, &Move::new(1, 2, 2)).is_err());
+        assert_eq!(crates, unchanged);
+    }
+}
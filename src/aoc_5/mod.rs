@@ -7,11 +7,18 @@ use std::{
 use lazy_static::lazy_static;
 use regex::Regex;
 
-use crate::get_input;
+pub mod crane;
+pub mod planner;
+pub mod yard;
 
-type Crates = Vec<VecDeque<Crate>>;
+use crate::{get_input, runner::Solution};
 
-struct Crate {
+use self::crane::{run, CrateMover9000, CrateMover9001};
+
+pub type Crates = Vec<VecDeque<Crate>>;
+
+#[derive(PartialEq, Eq, Hash, Clone, Debug)]
+pub struct Crate {
     name: String, //could use char here instead but that means more conversion nonsense
 }
 
@@ -43,6 +50,11 @@ fn parse_crates(input_iter: &mut Iter<String>) -> Crates {
 
     while let Some(line) = input_iter.next() {
         if line.contains(|c: char| c.is_digit(10)) {
+            //the numbered base line counts the stacks, even those without any crate
+            let stacks = line.split_whitespace().count();
+            if stacks > result.len() {
+                result.resize(stacks, VecDeque::new());
+            }
             break;
         }
 
@@ -87,44 +99,22 @@ impl From<&str> for Move {
     }
 }
 
-#[derive(PartialEq, Eq, Debug)]
-struct Move {
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Move {
     nb: usize,
     from: usize,
     to: usize,
 }
 
 impl Move {
-    fn do_move<F: Fn(&mut VecDeque<Crate>) -> Vec<Crate>>(
-        &self,
-        mut crates: Crates,
-        drain_fn: F,
-    ) -> Crates {
-        let to_move: Vec<Crate> = {
-            let from = &mut crates[self.from - 1];
-            drain_fn(from) //need to collect so the compiler does not complain about mutating crates from 2 references
-        };
-
-        let to = &mut crates[self.to - 1];
-        for c in to_move {
-            to.push_back(c);
-        }
-        crates
-    }
-
-    fn do_move_one_by_one(&self, crates: Crates) -> Crates {
-        self.do_move(crates, |from| {
-            let mut to_move: Vec<Crate> = from.drain(from.len() - self.nb..).collect();
-            to_move.reverse(); // reverse so the head (last item of the deque) is inserted first in the new queue
-            to_move
-        })
+    pub fn new(nb: usize, from: usize, to: usize) -> Self {
+        Move { nb, from, to }
     }
+}
 
-    fn do_move_by_stack(&self, crates: Crates) -> Crates {
-        self.do_move(crates, |from| {
-            let to_move: Vec<Crate> = from.drain(from.len() - self.nb..).collect();
-            to_move
-        })
+impl Display for Move {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "move {} from {} to {}", self.nb, self.from, self.to)
     }
 }
 
@@ -142,36 +132,100 @@ fn parse(input_iter: &mut Iter<String>) -> (Crates, Vec<Move>) {
     (parse_crates(input_iter), parse_move(input_iter))
 }
 
-pub fn aoc_5_1() {
-    let input = get_input("resource/aoc_5/data.txt");
-
-    let (mut crates, moves) = parse(&mut input.iter());
-
-    for m in moves {
-        crates = m.do_move_one_by_one(crates);
-    }
+/// The starting stacks and the move list of a puzzle input
+pub fn parse_input(input: &[String]) -> (Crates, Vec<Move>) {
+    parse(&mut input.iter())
+}
 
-    let code: String = crates
+fn top_code(crates: &Crates) -> String {
+    crates
         .iter()
         .map(|pile| pile.back().map_or(String::from(""), |c| c.to_string()))
+        .collect()
+}
+
+/// The puzzle drawing of the stacks: one line per level from the top,
+/// every line padded to the full width, then the stack numbers
+pub fn draw_crates(crates: &Crates) -> Vec<String> {
+    let height = crates.iter().map(|pile| pile.len()).max().unwrap_or(0);
+
+    let mut lines: Vec<String> = (0..height)
+        .rev()
+        .map(|level| {
+            crates
+                .iter()
+                .map(|pile| {
+                    pile.get(level)
+                        .map_or("   ".to_string(), |c| format!("[{}]", c))
+                })
+                .collect::<Vec<String>>()
+                .join(" ")
+        })
         .collect();
 
-    println!("AOC-5-1 Crates code {}", &code);
+    lines.push(
+        (1..=crates.len())
+            .map(|nb| format!("{:^3}", nb))
+            .collect::<Vec<String>>()
+            .join(" "),
+    );
+
+    lines
 }
 
-pub fn aoc_5_2() {
-    let input = get_input("resource/aoc_5/data.txt");
+/// A whole puzzle input, e.g. to regenerate one after some moves
+pub fn write_input(crates: &Crates, moves: &[Move]) -> Vec<String> {
+    let mut lines = draw_crates(crates);
+    lines.push(String::new());
+    lines.extend(moves.iter().map(|m| m.to_string()));
+    lines
+}
+
+pub(crate) struct Part1;
+pub(crate) struct Part2;
 
-    let (mut crates, moves) = parse(&mut input.iter());
+impl Solution for Part1 {
+    type Parsed = (Crates, Vec<Move>);
+    type Output = String;
 
-    for m in moves {
-        crates = m.do_move_by_stack(crates);
+    fn parse(&self, input: Vec<String>) -> Self::Parsed {
+        parse(&mut input.iter())
     }
 
-    let code: String = crates
-        .iter()
-        .map(|pile| pile.back().map_or(String::from(""), |c| c.to_string()))
-        .collect();
+    fn solve(&self, (crates, moves): Self::Parsed) -> Self::Output {
+        let crates = run(&CrateMover9000, crates, &moves).unwrap_or_else(|e| panic!("{}", e));
+
+        top_code(&crates)
+    }
+}
+
+impl Solution for Part2 {
+    type Parsed = (Crates, Vec<Move>);
+    type Output = String;
+
+    fn parse(&self, input: Vec<String>) -> Self::Parsed {
+        parse(&mut input.iter())
+    }
+
+    fn solve(&self, (crates, moves): Self::Parsed) -> Self::Output {
+        let crates = run(&CrateMover9001, crates, &moves).unwrap_or_else(|e| panic!("{}", e));
+
+        top_code(&crates)
+    }
+}
+
+pub fn aoc_5_1() {
+    let input = get_input("resource/aoc_5/data.txt");
+
+    let code = Part1.solve(Part1.parse(input));
+
+    println!("AOC-5-1 Crates code {}", &code);
+}
+
+pub fn aoc_5_2() {
+    let input = get_input("resource/aoc_5/data.txt");
+
+    let code = Part2.solve(Part2.parse(input));
 
     println!("AOC-5-2 Crates code {}", &code);
 }
@@ -179,6 +233,7 @@ pub fn aoc_5_2() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::aoc_5::crane::apply;
 
     #[test]
     fn test_parse() {
@@ -234,7 +289,7 @@ mod tests {
     }
 
     #[test]
-    fn test_move_one_by_one() {
+    fn test_crate_mover_9000() {
         let input = vec![
             "    [D]    ",
             "[N] [C]    ",
@@ -253,9 +308,9 @@ mod tests {
 
         let (mut crates, moves) = parse(&mut iter);
 
-        for m in moves {
-            crates = m.do_move_one_by_one(crates);
-            print_crates(&crates);
+        for (i, m) in moves.iter().enumerate() {
+            apply(&CrateMover9000, &mut crates, i + 1, m).unwrap();
+            println!("{}", draw_crates(&crates).join("\n"));
         }
 
         itertools::assert_equal(crates[0].iter().map(|c| c.to_string()), vec!["C"]);
@@ -267,7 +322,7 @@ mod tests {
     }
 
     #[test]
-    fn test_move_by_stack() {
+    fn test_crate_mover_9001() {
         let input = vec![
             "    [D]    ",
             "[N] [C]    ",
@@ -286,9 +341,9 @@ mod tests {
 
         let (mut crates, moves) = parse(&mut iter);
 
-        for m in moves {
-            crates = m.do_move_by_stack(crates);
-            print_crates(&crates);
+        for (i, m) in moves.iter().enumerate() {
+            apply(&CrateMover9001, &mut crates, i + 1, m).unwrap();
+            println!("{}", draw_crates(&crates).join("\n"));
         }
 
         itertools::assert_equal(crates[0].iter().map(|c| c.to_string()), vec!["M"]);
@@ -299,6 +354,62 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_draw() {
+        let input = vec![
+            "    [D]    ",
+            "[N] [C]    ",
+            "[Z] [M] [P]",
+            " 1   2   3 ",
+            "",
+            "move 1 from 2 to 1",
+            "move 3 from 1 to 3",
+        ];
+
+        let input_as_vec: Vec<String> = input.iter().map(|s| s.to_string()).collect();
+
+        let (crates, moves) = parse(&mut input_as_vec.iter());
+
+        itertools::assert_equal(write_input(&crates, &moves), input);
+
+        let crates = run(&CrateMover9000, crates, &moves).unwrap();
+
+        itertools::assert_equal(
+            draw_crates(&crates),
+            vec![
+                "        [Z]",
+                "        [N]",
+                "    [C] [D]",
+                "    [M] [P]",
+                " 1   2   3 ",
+            ],
+        );
+
+        assert_eq!(vec![" 1 "], draw_crates(&vec![VecDeque::new()]));
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let input = get_input("resource/aoc_5/data.txt");
+        let (crates, moves) = parse(&mut input.iter());
+
+        itertools::assert_equal(write_input(&crates, &moves).iter(), input.iter());
+
+        let crates = run(&CrateMover9001, crates, &moves).unwrap();
+        let drawing = draw_crates(&crates);
+        let (parsed, _) = parse(&mut drawing.iter());
+
+        assert_eq!(crates, parsed);
+
+        let empty: Crates = vec![VecDeque::new(); 3];
+        let drawing = write_input(&empty, &[]);
+        assert_eq!(vec![" 1   2   3 ", ""], drawing);
+        let (parsed, moves) = parse(&mut drawing.iter());
+
+        assert_eq!(empty, parsed);
+        assert!(moves.is_empty());
+    }
+
     #[test]
     fn test_aoc_5_1() {
         aoc_5_1();
@@ -308,12 +419,4 @@ mod tests {
     fn test_aoc_5_2() {
         aoc_5_2();
     }
-
-    fn print_crates(crates: &Crates) {
-        for i in 0..crates.len() {
-            for j in 0..crates[i].len() {
-                println!("{}-{}: {}", i + 1, j + 1, crates[i][j]);
-            }
-        }
-    }
 }
@@ -0,0 +1,339 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap},
+};
+
+use itertools::Itertools;
+
+use super::{
+    crane::{apply, Crane},
+    top_code, Crates, Move,
+};
+
+/// What the stacks should look like once the moves are done
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Target {
+    Arrangement(Crates),
+    /// Crates on top of the stacks, empty stacks skipped, as given by the puzzle answer
+    Tops(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Plan {
+    pub moves: Vec<Move>,
+    /// `false` if the search had to trade optimality for speed
+    pub shortest: bool,
+}
+
+impl Plan {
+    /// The moves in the puzzle format
+    pub fn lines(&self) -> Vec<String> {
+        self.moves.iter().map(|m| m.to_string()).collect()
+    }
+}
+
+/// Searches a move list from the initial stacks to a target with A*.
+/// A plain A* gives a shortest plan, when it visits more than `max_states` states
+/// the heuristic is weighted more and more so a longer plan is found sooner.
+pub struct Planner<'a, C: Crane + ?Sized> {
+    pub crane: &'a C,
+    pub max_states: usize,
+}
+
+/// Weights of the heuristic tried in turn, the first one keeps the plan shortest
+const WEIGHTS: [usize; 3] = [1, 4, 64];
+
+impl<'a, C: Crane + ?Sized> Planner<'a, C> {
+    pub fn new(crane: &'a C) -> Self {
+        Planner {
+            crane,
+            max_states: 100_000,
+        }
+    }
+
+    pub fn plan(&self, initial: &Crates, target: &Target) -> Result<Plan, String> {
+        check_target(initial, target)?;
+
+        for weight in WEIGHTS {
+            if let Some(moves) = self.search(initial, target, weight) {
+                return Ok(Plan {
+                    moves,
+                    shortest: weight == 1,
+                });
+            }
+        }
+
+        Err(format!("No plan found within {} states", self.max_states))
+    }
+
+    /// `None` if more than `max_states` states are visited
+    #[allow(clippy::unnecessary_map_or)]
+    fn search(&self, initial: &Crates, target: &Target, weight: usize) -> Option<Vec<Move>> {
+        let mut states: Vec<(Crates, Option<(usize, Move)>)> = vec![(initial.clone(), None)];
+        let mut best: HashMap<Crates, usize> = HashMap::from([(initial.clone(), 0)]);
+        let mut queue = BinaryHeap::from([Reverse((
+            weight * estimate(initial, target, weight == 1),
+            0,
+            0,
+        ))]);
+
+        while let Some(Reverse((_, cost, id))) = queue.pop() {
+            if best.get(&states[id].0).map_or(false, |b| *b < cost) {
+                continue; //reached again with fewer moves since
+            }
+            if reached(&states[id].0, target) {
+                return Some(path(&states, id));
+            }
+            if states.len() > self.max_states {
+                return None;
+            }
+
+            for m in candidates(&states[id].0) {
+                let mut next = states[id].0.clone();
+                if apply(self.crane, &mut next, 0, &m).is_err() {
+                    continue;
+                }
+                if best.get(&next).map_or(false, |b| *b <= cost + 1) {
+                    continue;
+                }
+
+                best.insert(next.clone(), cost + 1);
+                let priority = cost + 1 + weight * estimate(&next, target, weight == 1);
+                queue.push(Reverse((priority, cost + 1, states.len())));
+                states.push((next, Some((id, m))));
+            }
+        }
+
+        None
+    }
+}
+
+/// Every crate of the target must be somewhere in the initial stacks
+fn check_target(initial: &Crates, target: &Target) -> Result<(), String> {
+    let names = |crates: &Crates| -> Vec<String> {
+        crates
+            .iter()
+            .flatten()
+            .map(|c| c.to_string())
+            .sorted()
+            .collect()
+    };
+
+    match target {
+        Target::Arrangement(crates) => {
+            if crates.len() != initial.len() {
+                return Err(format!(
+                    "Expect {} stacks in the target, got {}",
+                    initial.len(),
+                    crates.len()
+                ));
+            }
+            if names(crates) != names(initial) {
+                return Err("The target does not hold the same crates".to_string());
+            }
+        }
+        Target::Tops(tops) => {
+            if tops.chars().count() > initial.len() {
+                return Err(format!("Only {} stacks for tops {}", initial.len(), tops));
+            }
+            let mut available = names(initial);
+            for c in tops.chars() {
+                match available.iter().position(|n| *n == c.to_string()) {
+                    Some(i) => {
+                        available.remove(i);
+                    }
+                    None => return Err(format!("Not enough crates {} for tops {}", c, tops)),
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn reached(crates: &Crates, target: &Target) -> bool {
+    match target {
+        Target::Arrangement(arrangement) => crates == arrangement,
+        Target::Tops(tops) => top_code(crates) == *tops,
+    }
+}
+
+/// Lower bound of the number of moves left: a move takes from one stack and puts on one.
+/// Once the plan does not have to be the shortest, the number of crates out of place
+/// guides the search better.
+#[allow(clippy::manual_div_ceil)]
+fn estimate(crates: &Crates, target: &Target, shortest: bool) -> usize {
+    match target {
+        Target::Arrangement(arrangement) => {
+            let (mut to_empty, mut to_fill, mut misplaced) = (0, 0, 0);
+            for (pile, wanted) in crates.iter().zip(arrangement.iter()) {
+                let kept = pile
+                    .iter()
+                    .zip(wanted.iter())
+                    .take_while(|(c, w)| c == w)
+                    .count();
+                to_empty += (pile.len() > kept) as usize;
+                to_fill += (wanted.len() > kept) as usize;
+                misplaced += pile.len() - kept;
+            }
+
+            if shortest {
+                to_empty.max(to_fill)
+            } else {
+                misplaced
+            }
+        }
+        Target::Tops(tops) => {
+            //a move changes the top of two stacks at most
+            let mut current: Vec<char> = top_code(crates).chars().collect();
+            let missing = tops
+                .chars()
+                .filter(|c| match current.iter().position(|t| t == c) {
+                    Some(i) => {
+                        current.swap_remove(i);
+                        false
+                    }
+                    None => true,
+                })
+                .count();
+
+            if shortest {
+                ((missing + 1) / 2).max(!reached(crates, target) as usize)
+            } else {
+                missing
+            }
+        }
+    }
+}
+
+fn candidates(crates: &Crates) -> Vec<Move> {
+    let mut moves = vec![];
+    for (from, pile) in crates.iter().enumerate() {
+        for to in (0..crates.len()).filter(|to| *to != from) {
+            for nb in 1..=pile.len() {
+                moves.push(Move::new(nb, from + 1, to + 1));
+            }
+        }
+    }
+    moves
+}
+
+fn path(states: &[(Crates, Option<(usize, Move)>)], mut id: usize) -> Vec<Move> {
+    let mut moves = vec![];
+    while let Some((parent, m)) = &states[id].1 {
+        moves.push(m.clone());
+        id = *parent;
+    }
+    moves.reverse();
+    moves
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aoc_5::{
+        crane::{run, CrateMover9000, CrateMover9001, Limited},
+        parse_input,
+    };
+
+    fn crates(drawing: &[&str]) -> Crates {
+        let lines: Vec<String> = drawing.iter().map(|s| s.to_string()).collect();
+        parse_input(&lines).0
+    }
+
+    fn initial() -> Crates {
+        crates(&["    [D]    ", "[N] [C]    ", "[Z] [M] [P]", " 1   2   3 "])
+    }
+
+    #[test]
+    fn test_plan_arrangement() {
+        let target = crates(&[
+            "        [Z]",
+            "        [N]",
+            "    [C] [D]",
+            "    [M] [P]",
+            " 1   2   3 ",
+        ]);
+
+        let plan = Planner::new(&CrateMover9000)
+            .plan(&initial(), &Target::Arrangement(target.clone()))
+            .unwrap();
+
+        assert!(plan.shortest);
+        assert_eq!(2, plan.moves.len());
+        assert_eq!(
+            target,
+            run(&CrateMover9000, initial(), &plan.moves).unwrap()
+        );
+
+        let plan = Plan {
+            moves: vec![Move::new(1, 2, 1), Move::new(3, 1, 3)],
+            shortest: true,
+        };
+        assert_eq!(
+            vec!["move 1 from 2 to 1", "move 3 from 1 to 3"],
+            plan.lines()
+        );
+    }
+
+    #[test]
+    fn test_plan_tops() {
+        let crane = Limited { capacity: 2 };
+        let plan = Planner::new(&crane)
+            .plan(&initial(), &Target::Tops("MZD".to_string()))
+            .unwrap();
+
+        let result = run(&crane, initial(), &plan.moves).unwrap();
+        assert_eq!("MZD", top_code(&result));
+        assert!(plan.shortest);
+
+        let done = Planner::new(&CrateMover9001)
+            .plan(&initial(), &Target::Tops("NDP".to_string()))
+            .unwrap();
+        assert!(done.moves.is_empty());
+    }
+
+    #[test]
+    fn test_near_shortest() {
+        let initial = crates(&[
+            "[A] [E] [I]",
+            "[B] [F] [J]",
+            "[C] [G] [K]",
+            "[D] [H] [L]",
+            " 1   2   3 ",
+        ]);
+        let target = crates(&[
+            "[L] [H] [D]",
+            "[K] [G] [C]",
+            "[J] [F] [B]",
+            "[I] [E] [A]",
+            " 1   2   3 ",
+        ]);
+
+        let planner = Planner {
+            crane: &CrateMover9001,
+            max_states: 1000,
+        };
+        let plan = planner
+            .plan(&initial, &Target::Arrangement(target.clone()))
+            .unwrap();
+
+        assert!(!plan.shortest);
+        assert_eq!(target, run(&CrateMover9001, initial, &plan.moves).unwrap());
+    }
+
+    #[test]
+    fn test_impossible_target() {
+        let planner = Planner::new(&CrateMover9000);
+
+        assert!(planner
+            .plan(&initial(), &Target::Tops("XYZ".to_string()))
+            .is_err());
+        assert!(planner
+            .plan(&initial(), &Target::Tops("ZZ".to_string()))
+            .is_err());
+        assert!(planner
+            .plan(&initial(), &Target::Arrangement(crates(&["[A]", " 1 "])))
+            .is_err());
+    }
+}
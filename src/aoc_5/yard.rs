@@ -0,0 +1,258 @@
+use std::collections::VecDeque;
+
+use super::{
+    crane::{apply, Crane, MoveError},
+    draw_crates, Crate, Crates, Move,
+};
+
+/// Replays a move list one move at a time, forward and backward.
+/// Only the crates lifted by each move are kept to undo it, not a copy of every state.
+pub struct Yard<'a, C: Crane + ?Sized> {
+    crane: &'a C,
+    initial: Crates,
+    crates: Crates,
+    moves: Vec<Move>,
+    /// Top of the `from` stack before each move done, bottom first
+    lifted: Vec<Vec<Crate>>,
+}
+
+impl<'a, C: Crane + ?Sized> Yard<'a, C> {
+    pub fn new(crane: &'a C, crates: Crates, moves: Vec<Move>) -> Self {
+        Yard {
+            crane,
+            initial: crates.clone(),
+            crates,
+            moves,
+            lifted: vec![],
+        }
+    }
+
+    /// Number of moves done
+    pub fn position(&self) -> usize {
+        self.lifted.len()
+    }
+
+    pub fn len(&self) -> usize {
+        self.moves.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.moves.is_empty()
+    }
+
+    pub fn crates(&self) -> &Crates {
+        &self.crates
+    }
+
+    pub fn draw(&self) -> Vec<String> {
+        draw_crates(&self.crates)
+    }
+
+    /// Moves done so far with their number, from 1
+    pub fn history(&self) -> impl Iterator<Item = (usize, &Move)> {
+        self.moves[..self.position()]
+            .iter()
+            .enumerate()
+            .map(|(i, m)| (i + 1, m))
+    }
+
+    /// Next move to do, if any
+    pub fn next_move(&self) -> Option<&Move> {
+        self.moves.get(self.position())
+    }
+
+    /// Does the next move, `false` if all the moves are done
+    pub fn forward(&mut self) -> Result<bool, MoveError> {
+        let number = self.position() + 1;
+        let m = match self.moves.get(number - 1) {
+            Some(m) => m,
+            None => return Ok(false),
+        };
+
+        let from = self.crates.get(m.from.wrapping_sub(1));
+        let lifted: Vec<Crate> = from
+            .map(|pile| {
+                pile.iter()
+                    .skip(pile.len().saturating_sub(m.nb))
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        apply(self.crane, &mut self.crates, number, m)?;
+        self.lifted.push(lifted);
+
+        Ok(true)
+    }
+
+    /// Undoes the last move done, `false` if no move is done
+    pub fn back(&mut self) -> bool {
+        match self.lifted.pop() {
+            None => false,
+            Some(lifted) => {
+                let m = &self.moves[self.position()];
+                let to = &mut self.crates[m.to - 1];
+                to.truncate(to.len() - m.nb);
+                self.crates[m.from - 1].extend(lifted);
+                true
+            }
+        }
+    }
+
+    /// Goes to the state after `position` moves, forward or backward
+    pub fn jump(&mut self, position: usize) -> Result<(), MoveError> {
+        let position = position.min(self.len());
+
+        while self.position() > position {
+            self.back();
+        }
+        while self.position() < position {
+            self.forward()?;
+        }
+
+        Ok(())
+    }
+
+    /// Numbers of the moves taking crates from or to `stack`, from 1
+    pub fn touching_stack(&self, stack: usize) -> Vec<usize> {
+        self.moves
+            .iter()
+            .enumerate()
+            .filter(|(_, m)| m.from == stack || m.to == stack)
+            .map(|(i, _)| i + 1)
+            .collect()
+    }
+
+    /// Numbers of the moves carrying the crate initially at `level` (from 1 at the bottom)
+    /// of `stack`, over the whole move list
+    #[allow(clippy::unnecessary_map_or)]
+    pub fn touching_crate(&self, stack: usize, level: usize) -> Result<Vec<usize>, MoveError> {
+        //same stacks with every crate named after its initial position, so it can be followed
+        let mut labels: Crates = self
+            .initial
+            .iter()
+            .enumerate()
+            .map(|(s, pile)| {
+                (0..pile.len())
+                    .map(|l| Crate {
+                        name: label(s + 1, l + 1),
+                    })
+                    .collect::<VecDeque<Crate>>()
+            })
+            .collect();
+        let followed = label(stack, level);
+
+        let mut touching = vec![];
+        for (i, m) in self.moves.iter().enumerate() {
+            let carried = labels.get(m.from.wrapping_sub(1)).map_or(false, |pile| {
+                pile.iter()
+                    .skip(pile.len().saturating_sub(m.nb))
+                    .any(|c| c.name == followed)
+            });
+
+            apply(self.crane, &mut labels, i + 1, m)?;
+            if carried {
+                touching.push(i + 1);
+            }
+        }
+
+        Ok(touching)
+    }
+}
+
+fn label(stack: usize, level: usize) -> String {
+    format!("{}:{}", stack, level)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aoc_5::{
+        crane::{run, CrateMover9000, CrateMover9001},
+        parse, top_code,
+    };
+
+    const INPUT: [&str; 9] = [
+        "    [D]    ",
+        "[N] [C]    ",
+        "[Z] [M] [P]",
+        " 1   2   3 ",
+        "",
+        "move 1 from 2 to 1",
+        "move 3 from 1 to 3",
+        "move 2 from 2 to 1",
+        "move 1 from 1 to 2",
+    ];
+
+    fn input() -> (Crates, Vec<Move>) {
+        let lines: Vec<String> = INPUT.iter().map(|s| s.to_string()).collect();
+        parse(&mut lines.iter())
+    }
+
+    #[test]
+    fn test_step() {
+        let (crates, moves) = input();
+        let mut yard = Yard::new(&CrateMover9000, crates.clone(), moves);
+
+        assert!(!yard.back());
+        assert_eq!(Ok(true), yard.forward());
+        assert_eq!("DCP", top_code(yard.crates()));
+        assert_eq!(Ok(true), yard.forward());
+        assert_eq!(Some(&Move::new(2, 2, 1)), yard.next_move());
+
+        assert!(yard.back());
+        assert!(yard.back());
+        assert_eq!(&crates, yard.crates());
+
+        yard.jump(4).unwrap();
+        assert_eq!("CMZ", top_code(yard.crates()));
+        assert_eq!(Ok(false), yard.forward());
+        assert_eq!(4, yard.history().count());
+
+        yard.jump(1).unwrap();
+        assert_eq!("DCP", top_code(yard.crates()));
+        yard.jump(0).unwrap();
+        assert_eq!(&crates, yard.crates());
+    }
+
+    #[test]
+    fn test_undo_data() {
+        let input = crate::get_input("resource/aoc_5/data.txt");
+        let (crates, moves) = parse(&mut input.iter());
+        let expected = run(&CrateMover9001, crates.clone(), &moves).unwrap();
+        let mut yard = Yard::new(&CrateMover9001, crates.clone(), moves);
+
+        yard.jump(yard.len()).unwrap();
+        assert_eq!(&expected, yard.crates());
+
+        yard.jump(0).unwrap();
+        assert_eq!(&crates, yard.crates());
+    }
+
+    #[test]
+    fn test_touching() {
+        let (crates, moves) = input();
+        let yard = Yard::new(&CrateMover9000, crates, moves);
+
+        assert_eq!(vec![1, 3, 4], yard.touching_stack(2));
+        assert_eq!(vec![2], yard.touching_stack(3));
+
+        //Z at the bottom of stack 1 only goes to stack 3
+        assert_eq!(Ok(vec![2]), yard.touching_crate(1, 1));
+        //D goes to stack 1, then stack 3
+        assert_eq!(Ok(vec![1, 2]), yard.touching_crate(2, 3));
+        //M is under C, moved with it to stack 1 then alone back to stack 2
+        assert_eq!(Ok(vec![3, 4]), yard.touching_crate(2, 1));
+        assert_eq!(Ok(vec![]), yard.touching_crate(3, 1));
+    }
+
+    #[test]
+    fn test_invalid_step() {
+        let (crates, _) = input();
+        let mut yard = Yard::new(&CrateMover9000, crates.clone(), vec![Move::new(5, 1, 2)]);
+
+        assert_eq!(1, yard.forward().unwrap_err().move_number);
+        assert_eq!(0, yard.position());
+        assert_eq!(&crates, yard.crates());
+    }
+}
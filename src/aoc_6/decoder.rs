@@ -0,0 +1,239 @@
+use std::{
+    fmt::Display,
+    io::{self, BufRead, Split},
+};
+
+use super::{Window, MESSAGE_MARKER, PACKET_MARKER};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    Packet,
+    Message,
+}
+
+impl Kind {
+    pub fn marker_size(&self) -> usize {
+        match self {
+            Kind::Packet => PACKET_MARKER,
+            Kind::Message => MESSAGE_MARKER,
+        }
+    }
+}
+
+impl Display for Kind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Kind::Packet => write!(f, "packet"),
+            Kind::Message => write!(f, "message"),
+        }
+    }
+}
+
+/// A marker and the data following it up to the next marker of the same kind
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    pub kind: Kind,
+    /// Position of the marker first byte in its line, from 0
+    pub start: usize,
+    pub marker: Vec<u8>,
+    pub payload: Vec<u8>,
+}
+
+impl Frame {
+    /// Bytes read in the line when the marker is complete, the puzzle answer for the first frame
+    pub fn end(&self) -> usize {
+        self.start + self.marker.len()
+    }
+}
+
+impl Display for Frame {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {}-{} {}, {} bytes",
+            self.kind,
+            self.start,
+            self.end(),
+            String::from_utf8_lossy(&self.marker),
+            self.payload.len()
+        )
+    }
+}
+
+/// One line of the stream split into packets, and independently into messages.
+/// Markers do not overlap: the search for the next one starts after the previous one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Datastream {
+    /// From 1
+    pub line: usize,
+    pub packets: Vec<Frame>,
+    pub messages: Vec<Frame>,
+}
+
+impl Datastream {
+    pub fn decode(line: usize, data: &[u8]) -> Self {
+        Datastream {
+            line,
+            packets: frames(data, Kind::Packet),
+            messages: frames(data, Kind::Message),
+        }
+    }
+
+    pub fn frames(&self, kind: Kind) -> &[Frame] {
+        match kind {
+            Kind::Packet => &self.packets,
+            Kind::Message => &self.messages,
+        }
+    }
+
+    /// End of every marker of the kind
+    pub fn markers(&self, kind: Kind) -> Vec<usize> {
+        self.frames(kind).iter().map(Frame::end).collect()
+    }
+}
+
+impl Display for Datastream {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "Line {}: {} packets, {} messages",
+            self.line,
+            self.packets.len(),
+            self.messages.len()
+        )?;
+        for frame in self.packets.iter().chain(self.messages.iter()) {
+            writeln!(f, "  {}", frame)?;
+        }
+        Ok(())
+    }
+}
+
+fn frames(data: &[u8], kind: Kind) -> Vec<Frame> {
+    let size = kind.marker_size();
+    let mut window = Window::new(size);
+
+    let mut ends = vec![];
+    for (i, byte) in data.iter().enumerate() {
+        if window.push(*byte) {
+            ends.push(i + 1);
+            window.clear();
+        }
+    }
+
+    ends.iter()
+        .enumerate()
+        .map(|(i, end)| {
+            let next = ends.get(i + 1).map_or(data.len(), |next| next - size);
+            Frame {
+                kind,
+                start: end - size,
+                marker: data[end - size..*end].to_vec(),
+                payload: data[*end..next].to_vec(),
+            }
+        })
+        .collect()
+}
+
+/// Decodes each line of a source as its own datastream
+pub struct Decoder<R: BufRead> {
+    lines: Split<R>,
+    line: usize,
+}
+
+impl<R: BufRead> Decoder<R> {
+    pub fn new(source: R) -> Self {
+        Decoder {
+            lines: source.split(b'\n'),
+            line: 0,
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for Decoder<R> {
+    type Item = io::Result<Datastream>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut data = match self.lines.next()? {
+            Ok(data) => data,
+            Err(e) => return Some(Err(e)),
+        };
+        if data.last() == Some(&b'\r') {
+            data.pop();
+        }
+
+        self.line += 1;
+        Some(Ok(Datastream::decode(self.line, &data)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aoc_6::first_marker;
+
+    #[test]
+    fn test_all_markers() {
+        let stream = Datastream::decode(1, b"mjqjpqmgbljsphdztnvjfqwrcgsmlb");
+
+        assert_eq!(vec![7, 11, 15, 19, 23, 27], stream.markers(Kind::Packet));
+        assert_eq!(vec![19], stream.markers(Kind::Message));
+
+        let first = &stream.packets[0];
+        assert_eq!(b"jpqm".to_vec(), first.marker);
+        assert_eq!(b"".to_vec(), first.payload);
+        assert_eq!(b"mlb".to_vec(), stream.packets[5].payload);
+        assert_eq!("packet 23-27 rcgs, 3 bytes", stream.packets[5].to_string());
+        assert_eq!(b"jfqwrcgsmlb".to_vec(), stream.messages[0].payload);
+    }
+
+    #[test]
+    fn test_payload_framing() {
+        //repeated bytes are no marker, so they stay in the payload
+        let stream = Datastream::decode(1, b"aaabcdeeefghhh");
+
+        let frames: Vec<String> = stream.packets.iter().map(|f| f.to_string()).collect();
+        assert_eq!(
+            vec!["packet 2-6 abcd, 2 bytes", "packet 8-12 efgh, 2 bytes"],
+            frames
+        );
+        assert!(stream.messages.is_empty());
+    }
+
+    #[test]
+    fn test_lines() {
+        let source = "bvwbjplbgvbhsrlpgdmjqwftvncz\r\naaaa\n\nnppdvjthqldpwncqszvftbrmjlhg";
+        let streams: Vec<Datastream> = Decoder::new(source.as_bytes())
+            .map(|s| s.unwrap())
+            .collect();
+
+        assert_eq!(4, streams.len());
+        assert_eq!(5, streams[0].packets[0].end());
+        assert_eq!(23, streams[0].messages[0].end());
+        assert!(streams[1].packets.is_empty());
+        assert_eq!(3, streams[2].line);
+        assert!(streams[2].messages.is_empty());
+        assert_eq!(4, streams[3].line);
+        assert_eq!(vec![23], streams[3].markers(Kind::Message));
+    }
+
+    #[test]
+    fn test_data() {
+        let file = std::fs::File::open("resource/aoc_6/data.txt").unwrap();
+        let input = crate::get_input("resource/aoc_6/data.txt");
+        let stream = Decoder::new(io::BufReader::new(file))
+            .next()
+            .unwrap()
+            .unwrap();
+
+        for kind in [Kind::Packet, Kind::Message] {
+            let frames = stream.frames(kind);
+            assert_eq!(first_marker(&input[0], kind.marker_size()), frames[0].end());
+            //markers and payloads cover the whole line after the first marker
+            let covered: usize = frames
+                .iter()
+                .map(|f| f.marker.len() + f.payload.len())
+                .sum();
+            assert_eq!(input[0].len() - frames[0].start, covered);
+        }
+    }
+}
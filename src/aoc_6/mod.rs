@@ -1,39 +1,129 @@
-use std::collections::{HashSet, VecDeque};
+use std::{
+    collections::VecDeque,
+    io::{self, BufReader, Read},
+};
+
+pub mod decoder;
+
+use crate::{get_input, runner::Solution};
+
+/// Characters all different at the start of a packet
+pub const PACKET_MARKER: usize = 4;
+/// Characters all different at the start of a message
+pub const MESSAGE_MARKER: usize = 14;
+
+/// Last `size` bytes of a stream, with a count per byte value
+/// to tell in O(1) whether they are all different
+struct Window {
+    size: usize,
+    bytes: VecDeque<u8>,
+    counts: [usize; 256],
+    /// Byte values present more than once
+    repeated: usize,
+}
 
-use crate::get_input;
+impl Window {
+    fn new(size: usize) -> Self {
+        Window {
+            size,
+            bytes: VecDeque::with_capacity(size + 1),
+            counts: [0; 256],
+            repeated: 0,
+        }
+    }
 
-fn find_marker<T: AsRef<str>>(s: T, marker_size: usize) -> usize {
-    let mut iter_chars = s.as_ref().chars();
-    let mut marker = iter_chars.by_ref().take(3).collect::<VecDeque<char>>();
+    /// Adds a byte, `true` if the window is then full of different bytes
+    fn push(&mut self, byte: u8) -> bool {
+        self.bytes.push_back(byte);
+        self.counts[byte as usize] += 1;
+        if self.counts[byte as usize] == 2 {
+            self.repeated += 1;
+        }
 
-    let mut position = 3;
+        if self.bytes.len() > self.size {
+            let out = self.bytes.pop_front().unwrap() as usize;
+            self.counts[out] -= 1;
+            if self.counts[out] == 1 {
+                self.repeated -= 1;
+            }
+        }
 
-    for c in iter_chars {
-        position += 1;
+        self.bytes.len() == self.size && self.repeated == 0
+    }
 
-        add(&mut marker, c, marker_size);
+    fn clear(&mut self) {
+        for byte in self.bytes.drain(..) {
+            self.counts[byte as usize] = 0;
+        }
+        self.repeated = 0;
+    }
+}
 
-        if marker.iter().copied().collect::<HashSet<char>>().len() == marker_size {
-            break; //we have 4 different characters in the set
+/// Number of bytes read when the last `marker_size` bytes are all different,
+/// `None` if the source ends before. Each byte is read once and the window keeps
+/// a count per byte value, so it runs in O(n) whatever the marker size.
+pub fn find_marker<R: Read>(source: R, marker_size: usize) -> io::Result<Option<usize>> {
+    if marker_size == 0 {
+        return Ok(Some(0));
+    }
+
+    let mut window = Window::new(marker_size);
+    for (position, byte) in BufReader::new(source).bytes().enumerate() {
+        if window.push(byte?) {
+            return Ok(Some(position + 1));
         }
     }
 
-    position
+    Ok(None)
+}
+
+fn first_marker(line: &str, marker_size: usize) -> usize {
+    find_marker(line.as_bytes(), marker_size)
+        .expect("Cannot read a string")
+        .unwrap_or_else(|| panic!("No marker of {} different characters", marker_size))
 }
 
-fn add(marker: &mut VecDeque<char>, c: char, marker_size: usize) {
-    marker.push_back(c);
-    if marker.len() > marker_size {
-        marker.pop_front();
+pub(crate) struct Part1;
+pub(crate) struct Part2;
+
+fn single_line<I: Iterator<Item = String>>(mut input: I) -> String {
+    let line = input.next().expect("Empty input");
+
+    assert!(input.next().is_none());
+
+    line
+}
+
+impl Solution for Part1 {
+    type Parsed = String;
+    type Output = usize;
+
+    fn parse(&self, input: Vec<String>) -> Self::Parsed {
+        single_line(input.into_iter())
+    }
+
+    fn solve(&self, parsed: Self::Parsed) -> Self::Output {
+        first_marker(&parsed, PACKET_MARKER)
+    }
+}
+
+impl Solution for Part2 {
+    type Parsed = String;
+    type Output = usize;
+
+    fn parse(&self, input: Vec<String>) -> Self::Parsed {
+        single_line(input.into_iter())
+    }
+
+    fn solve(&self, parsed: Self::Parsed) -> Self::Output {
+        first_marker(&parsed, MESSAGE_MARKER)
     }
 }
 
 pub fn aoc_6_1() {
     let input = get_input("resource/aoc_6/data.txt");
 
-    assert!(input.len() == 1);
-
-    let first_marker = find_marker(input.first().unwrap(), 4);
+    let first_marker = Part1.solve(Part1.parse(input));
 
     println!("AOC-6-1 first marker 4 {}", first_marker);
 }
@@ -41,9 +131,7 @@ pub fn aoc_6_1() {
 pub fn aoc_6_2() {
     let input = get_input("resource/aoc_6/data.txt");
 
-    assert!(input.len() == 1);
-
-    let first_marker = find_marker(input.first().unwrap(), 14);
+    let first_marker = Part2.solve(Part2.parse(input));
 
     println!("AOC-6-2 first marker 14 {}", first_marker);
 }
@@ -52,6 +140,10 @@ pub fn aoc_6_2() {
 mod tests {
     use super::*;
 
+    fn marker(s: &str, marker_size: usize) -> Option<usize> {
+        find_marker(s.as_bytes(), marker_size).unwrap()
+    }
+
     #[test]
     fn test_aoc_6_1() {
         aoc_6_1();
@@ -64,18 +156,38 @@ mod tests {
 
     #[test]
     fn test_find_first_4() {
-        assert_eq!(5, find_marker("bvwbjplbgvbhsrlpgdmjqwftvncz", 4));
-        assert_eq!(6, find_marker("nppdvjthqldpwncqszvftbrmjlhg", 4));
-        assert_eq!(10, find_marker("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg", 4));
-        assert_eq!(11, find_marker("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw", 4));
+        assert_eq!(Some(5), marker("bvwbjplbgvbhsrlpgdmjqwftvncz", 4));
+        assert_eq!(Some(6), marker("nppdvjthqldpwncqszvftbrmjlhg", 4));
+        assert_eq!(Some(10), marker("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg", 4));
+        assert_eq!(Some(11), marker("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw", 4));
     }
 
     #[test]
     fn test_find_first_14() {
-        assert_eq!(19, find_marker("mjqjpqmgbljsphdztnvjfqwrcgsmlb", 14));
-        assert_eq!(23, find_marker("bvwbjplbgvbhsrlpgdmjqwftvncz", 14));
-        assert_eq!(23, find_marker("nppdvjthqldpwncqszvftbrmjlhg", 14));
-        assert_eq!(29, find_marker("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg", 14));
-        assert_eq!(26, find_marker("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw", 14));
+        assert_eq!(Some(19), marker("mjqjpqmgbljsphdztnvjfqwrcgsmlb", 14));
+        assert_eq!(Some(23), marker("bvwbjplbgvbhsrlpgdmjqwftvncz", 14));
+        assert_eq!(Some(23), marker("nppdvjthqldpwncqszvftbrmjlhg", 14));
+        assert_eq!(Some(29), marker("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg", 14));
+        assert_eq!(Some(26), marker("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw", 14));
+    }
+
+    #[test]
+    fn test_marker_sizes() {
+        assert_eq!(Some(1), marker("a", 1));
+        assert_eq!(Some(3), marker("aab", 2));
+        assert_eq!(Some(0), marker("", 0));
+        assert_eq!(None, marker("abab", 3));
+        assert_eq!(None, marker("", 4));
+    }
+
+    #[test]
+    fn test_marker_from_file() {
+        let file = std::fs::File::open("resource/aoc_6/data.txt").unwrap();
+        let input = get_input("resource/aoc_6/data.txt");
+
+        assert_eq!(
+            Some(first_marker(&input[0], 14)),
+            find_marker(file, 14).unwrap()
+        );
     }
 }
@@ -0,0 +1,370 @@
+use std::{collections::HashSet, fmt::Display};
+
+use super::{parse_listing, NodeId, Tree, ROOT};
+
+/// How to resolve what a transcript says twice, or does not say
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Policy {
+    /// Entries of every listing are kept, with what was listed first
+    Merge,
+    /// The last listing of a folder replaces the previous ones
+    LastWins,
+    /// The first anomaly stops the build
+    Error,
+}
+
+/// `merge`, `last-wins` or `error`
+impl TryFrom<&str> for Policy {
+    type Error = String;
+
+    fn try_from(value: &str) -> Result<Self, String> {
+        match value {
+            "merge" => Ok(Policy::Merge),
+            "last-wins" => Ok(Policy::LastWins),
+            "error" => Ok(Policy::Error),
+            _ => Err(format!("Unknown policy {}", value)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AnomalyKind {
+    /// A folder listed once more
+    Relisted { path: String },
+    ConflictingSize {
+        path: String,
+        previous: u64,
+        listed: u64,
+    },
+    /// A name listed as a file and as a folder
+    ConflictingType { path: String },
+    /// `cd` into a folder no listing shows
+    UnlistedCd { path: String },
+}
+
+/// Something odd in a transcript, at its line counted from 1
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Anomaly {
+    pub line: usize,
+    pub kind: AnomalyKind,
+}
+
+impl Display for Anomaly {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: ", self.line)?;
+        match &self.kind {
+            AnomalyKind::Relisted { path } => write!(f, "{} is listed again", path),
+            AnomalyKind::ConflictingSize {
+                path,
+                previous,
+                listed,
+            } => write!(
+                f,
+                "{} is listed with size {}, was {}",
+                path, listed, previous
+            ),
+            AnomalyKind::ConflictingType { path } => {
+                write!(f, "{} is listed as a file and a folder", path)
+            }
+            AnomalyKind::UnlistedCd { path } => write!(f, "cd into {}, never listed", path),
+        }
+    }
+}
+
+/// A tree with the anomalies met while building it, in transcript order
+#[derive(Debug, Clone)]
+pub struct Build {
+    pub tree: Tree,
+    pub anomalies: Vec<Anomaly>,
+}
+
+/// Builds the tree of a transcript, resolving the anomalies by the policy.
+/// Lines that are not part of a transcript are an error with any policy.
+pub fn build<I: Iterator<Item = String>>(lines: I, policy: Policy) -> Result<Build, String> {
+    let mut builder = Builder {
+        policy,
+        tree: Tree::new(),
+        cwd: ROOT,
+        listed: HashSet::new(),
+        anomalies: vec![],
+    };
+
+    let mut lines = lines
+        .enumerate()
+        .map(|(i, line)| (i + 1, line))
+        .filter(|(_, line)| !line.is_empty())
+        .peekable();
+
+    while let Some((number, line)) = lines.next() {
+        match line.as_str() {
+            "$ cd /" => builder.cwd = ROOT,
+            "$ cd .." => builder.cwd = builder.tree.parent(builder.cwd).unwrap_or(ROOT),
+            "$ ls" => {
+                let mut entries = vec![];
+                while let Some(entry) = lines.next_if(|(_, line)| !line.starts_with('$')) {
+                    entries.push(entry);
+                }
+                builder.list(number, &entries)?;
+            }
+            _ => match line.strip_prefix("$ cd ") {
+                Some(name) => builder.cd(number, name)?,
+                None => return Err(format!("line {}: Unknown command {}", number, line)),
+            },
+        }
+    }
+
+    Ok(Build {
+        tree: builder.tree,
+        anomalies: builder.anomalies,
+    })
+}
+
+struct Builder {
+    policy: Policy,
+    tree: Tree,
+    cwd: NodeId,
+    /// Folders listed at least once
+    listed: HashSet<NodeId>,
+    anomalies: Vec<Anomaly>,
+}
+
+impl Builder {
+    fn report(&mut self, line: usize, kind: AnomalyKind) -> Result<(), String> {
+        let anomaly = Anomaly { line, kind };
+        if self.policy == Policy::Error {
+            return Err(anomaly.to_string());
+        }
+        self.anomalies.push(anomaly);
+        Ok(())
+    }
+
+    fn cd(&mut self, line: usize, name: &str) -> Result<(), String> {
+        self.cwd = match self.tree.child(self.cwd, name) {
+            Some(child) if self.tree.is_dir(child) => child,
+            Some(child) => {
+                return Err(format!(
+                    "line {}: Cannot cd into the file {}",
+                    line,
+                    self.tree.path(child)
+                ))
+            }
+            None => {
+                let child = self
+                    .tree
+                    .add_dir(self.cwd, name)
+                    .map_err(|e| format!("line {}: {}", line, e))?;
+                let path = self.tree.path(child);
+                self.report(line, AnomalyKind::UnlistedCd { path })?;
+                child
+            }
+        };
+        Ok(())
+    }
+
+    /// `entries` are the lines following `$ ls`, with their number
+    fn list(&mut self, line: usize, entries: &[(usize, String)]) -> Result<(), String> {
+        let dir = self.cwd;
+        if !self.listed.insert(dir) {
+            let path = self.tree.path(dir);
+            self.report(line, AnomalyKind::Relisted { path })?;
+        }
+
+        let mut shown = HashSet::new();
+        for (number, entry) in entries {
+            let (name, size) =
+                parse_listing(entry).map_err(|e| format!("line {}: {}", number, e))?;
+            shown.insert(self.entry(*number, dir, name, size)?);
+        }
+
+        if self.policy == Policy::LastWins {
+            let missing: Vec<NodeId> = self
+                .tree
+                .children(dir)
+                .iter()
+                .filter(|child| !shown.contains(*child))
+                .copied()
+                .collect();
+            for node in missing {
+                self.remove(node);
+            }
+        }
+        Ok(())
+    }
+
+    /// Adds a listed entry, or resolves a conflict with the one already there
+    fn entry(
+        &mut self,
+        line: usize,
+        dir: NodeId,
+        name: &str,
+        size: Option<u64>,
+    ) -> Result<NodeId, String> {
+        let error = |e: String| format!("line {}: {}", line, e);
+        let existing = match self.tree.child(dir, name) {
+            Some(existing) => existing,
+            None => return self.add(dir, name, size).map_err(error),
+        };
+        let path = self.tree.path(existing);
+
+        match (self.tree.is_dir(existing), size) {
+            (true, None) => Ok(existing),
+            (false, Some(size)) if self.tree.size(existing) == size => Ok(existing),
+            (false, Some(size)) => {
+                let previous = self.tree.size(existing);
+                self.report(
+                    line,
+                    AnomalyKind::ConflictingSize {
+                        path,
+                        previous,
+                        listed: size,
+                    },
+                )?;
+                if self.policy == Policy::LastWins {
+                    self.tree.set_size(existing, size).map_err(error)?;
+                }
+                Ok(existing)
+            }
+            _ => {
+                self.report(line, AnomalyKind::ConflictingType { path })?;
+                if self.policy != Policy::LastWins {
+                    return Ok(existing);
+                }
+                self.remove(existing);
+                self.add(dir, name, size).map_err(error)
+            }
+        }
+    }
+
+    fn add(&mut self, dir: NodeId, name: &str, size: Option<u64>) -> Result<NodeId, String> {
+        match size {
+            None => self.tree.add_dir(dir, name),
+            Some(size) => self.tree.add_file(dir, name, size),
+        }
+    }
+
+    /// Entries of the current folder only, never the root or the current folder
+    fn remove(&mut self, node: NodeId) {
+        for removed in self.tree.descendants(node) {
+            self.listed.remove(&removed);
+        }
+        self.tree
+            .remove(node)
+            .expect("Only entries of a listing are removed");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aoc_7::transcript::{transcript, Options};
+
+    const SESSION: [&str; 22] = [
+        "$ cd /", "$ ls", "dir a", "100 b", "7 old", "$ cd a", "$ ls", "10 c", "dir d", "$ cd ..",
+        "$ ls", "dir a", "200 b", "50 e", "$ cd x", "$ ls", "5 y", "$ cd /", "$ cd a", "$ ls",
+        "20 c", "30 d",
+    ];
+
+    fn build_session(policy: Policy) -> Result<Build, String> {
+        build(SESSION.iter().map(|l| l.to_string()), policy)
+    }
+
+    fn size(tree: &Tree, path: &str) -> u64 {
+        tree.size(tree.lookup(path).unwrap())
+    }
+
+    #[test]
+    fn test_anomalies() {
+        let built = build_session(Policy::Merge).unwrap();
+        let path = |p: &str| p.to_string();
+        let expected = [
+            (11, AnomalyKind::Relisted { path: path("/") }),
+            (
+                13,
+                AnomalyKind::ConflictingSize {
+                    path: path("/b"),
+                    previous: 100,
+                    listed: 200,
+                },
+            ),
+            (15, AnomalyKind::UnlistedCd { path: path("/x") }),
+            (20, AnomalyKind::Relisted { path: path("/a") }),
+            (
+                21,
+                AnomalyKind::ConflictingSize {
+                    path: path("/a/c"),
+                    previous: 10,
+                    listed: 20,
+                },
+            ),
+            (22, AnomalyKind::ConflictingType { path: path("/a/d") }),
+        ]
+        .map(|(line, kind)| Anomaly { line, kind });
+        assert_eq!(expected.to_vec(), built.anomalies);
+        assert_eq!(
+            "line 13: /b is listed with size 200, was 100",
+            built.anomalies[1].to_string()
+        );
+
+        let last_wins = build_session(Policy::LastWins).unwrap();
+        assert_eq!(built.anomalies, last_wins.anomalies);
+
+        assert_eq!(
+            Err("line 11: / is listed again".to_string()),
+            build_session(Policy::Error).map(|b| b.anomalies)
+        );
+    }
+
+    #[test]
+    fn test_policies() {
+        let tree = build_session(Policy::Merge).unwrap().tree;
+        assert_eq!(10 + 100 + 7 + 50 + 5, tree.size(ROOT));
+        assert_eq!(10, size(&tree, "/a"));
+        assert_eq!(100, size(&tree, "/b"));
+        assert!(tree.is_dir(tree.lookup("/a/d").unwrap()));
+
+        let tree = build_session(Policy::LastWins).unwrap().tree;
+        assert_eq!(50 + 200 + 50 + 5, tree.size(ROOT));
+        assert_eq!(30, size(&tree, "/a/d"));
+        assert!(tree.lookup("/old").is_err());
+        assert_eq!(5, size(&tree, "/x"));
+    }
+
+    #[test]
+    fn test_relisted_transcript() {
+        let tree = build(
+            crate::get_input("resource/aoc_7/data.txt").into_iter(),
+            Policy::Error,
+        )
+        .unwrap()
+        .tree;
+
+        let options = Options {
+            listings: 3,
+            ..Options::default()
+        };
+        let folders = tree.descendants(ROOT).filter(|n| tree.is_dir(*n)).count();
+        for policy in [Policy::Merge, Policy::LastWins] {
+            let built = build(transcript(&tree, &options).into_iter(), policy).unwrap();
+            assert_eq!(2 * folders, built.anomalies.len());
+            assert!(built
+                .anomalies
+                .iter()
+                .all(|a| matches!(a.kind, AnomalyKind::Relisted { .. })));
+            assert_eq!(tree.to_string(), built.tree.to_string());
+        }
+    }
+
+    #[test]
+    fn test_invalid_transcripts() {
+        let invalid: [&[&str]; 3] = [&["$ ls", "1 a", "$ cd a"], &["$ ls", "a b"], &["$ rm a"]];
+
+        for lines in invalid {
+            for policy in [Policy::Merge, Policy::LastWins, Policy::Error] {
+                let built = build(lines.iter().map(|l| l.to_string()), policy);
+                assert!(built.is_err(), "{:?}", lines);
+            }
+        }
+        assert_eq!(Ok(Policy::LastWins), Policy::try_from("last-wins"));
+        assert!(Policy::try_from("first").is_err());
+    }
+}
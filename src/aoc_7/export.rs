@@ -0,0 +1,294 @@
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use super::{NodeId, Tree, ROOT};
+
+/// A node with everything below it, as written in JSON
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum Entry {
+    Dir {
+        name: String,
+        size: u64,
+        children: Vec<Entry>,
+    },
+    File {
+        name: String,
+        size: u64,
+    },
+}
+
+impl Tree {
+    pub fn entry(&self, node: NodeId) -> Entry {
+        let name = self.name(node).to_string();
+        let size = self.size(node);
+
+        if self.is_dir(node) {
+            let children = self.children(node).iter().map(|c| self.entry(*c)).collect();
+            Entry::Dir {
+                name,
+                size,
+                children,
+            }
+        } else {
+            Entry::File { name, size }
+        }
+    }
+
+    /// Folder sizes must be the total of the files below them
+    pub fn from_entry(root: &Entry) -> Result<Tree, String> {
+        let mut tree = Tree::new();
+
+        match root {
+            Entry::Dir { name, .. } if name == "/" => add_entries(&mut tree, ROOT, root)?,
+            _ => return Err("The root must be a dir named /".to_string()),
+        }
+
+        Ok(tree)
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(&self.entry(ROOT)).expect("A tree is always serializable")
+    }
+
+    pub fn from_json(json: &str) -> Result<Tree, String> {
+        let root: Entry = serde_json::from_str(json).map_err(|e| e.to_string())?;
+        Tree::from_entry(&root)
+    }
+
+    /// Reads back a tree drawn by its `Display`
+    pub fn from_drawing<'a, I: Iterator<Item = &'a str>>(lines: I) -> Result<Tree, String> {
+        lazy_static! {
+            static ref LINE_REX: Regex = Regex::new(
+                r"^(?P<indent> *)- (?P<name>.+) \((?P<kind>dir|file), size=(?P<size>[0-9]+)\)$"
+            )
+            .unwrap();
+        }
+
+        let mut tree = Tree::new();
+        //folders from the root to the last one drawn
+        let mut folders: Vec<NodeId> = vec![];
+        let mut drawn_sizes = vec![];
+
+        for (i, line) in lines.enumerate().filter(|(_, line)| !line.is_empty()) {
+            let error = |message: &str| format!("line {}: {} {}", i + 1, message, line);
+            let captures = LINE_REX
+                .captures(line)
+                .ok_or_else(|| error("Drawing format is not correct"))?;
+            let indent = captures["indent"].len();
+            let name = &captures["name"];
+            let size: u64 = captures["size"]
+                .parse()
+                .map_err(|_| error("Size too large"))?;
+            let is_dir = &captures["kind"] == "dir";
+
+            if indent % 2 != 0 || indent / 2 > folders.len() {
+                return Err(error("Indentation is not correct"));
+            }
+            let depth = indent / 2;
+
+            if depth == 0 {
+                if !folders.is_empty() || name != "/" || !is_dir {
+                    return Err(error("The root must be the first line, a dir named /"));
+                }
+                folders.push(ROOT);
+                drawn_sizes.push((ROOT, size));
+                continue;
+            }
+
+            check_name(name).map_err(|e| error(&e))?;
+            folders.truncate(depth);
+            let parent = folders[depth - 1];
+            if is_dir {
+                let node = tree.add_dir(parent, name).map_err(|e| error(&e))?;
+                folders.push(node);
+                drawn_sizes.push((node, size));
+            } else {
+                tree.add_file(parent, name, size).map_err(|e| error(&e))?;
+            }
+        }
+
+        if folders.is_empty() {
+            return Err("The drawing is empty".to_string());
+        }
+        for (node, size) in drawn_sizes {
+            check_size(&tree, node, size)?;
+        }
+
+        Ok(tree)
+    }
+}
+
+fn add_entries(tree: &mut Tree, dir: NodeId, entry: &Entry) -> Result<(), String> {
+    if let Entry::Dir { children, size, .. } = entry {
+        for child in children {
+            match child {
+                Entry::Dir { name, .. } => {
+                    check_name(name)?;
+                    let node = tree.add_dir(dir, name)?;
+                    add_entries(tree, node, child)?;
+                }
+                Entry::File { name, size } => {
+                    check_name(name)?;
+                    tree.add_file(dir, name, *size)?;
+                }
+            }
+        }
+        check_size(tree, dir, *size)?;
+    }
+
+    Ok(())
+}
+
+/// Names must keep paths unambiguous and fit on one line of a drawing or a transcript
+fn check_name(name: &str) -> Result<(), String> {
+    if name.is_empty() || name == "." || name == ".." || name.contains(['/', '\n']) {
+        return Err(format!("Invalid name {:?}", name));
+    }
+    Ok(())
+}
+
+fn check_size(tree: &Tree, dir: NodeId, size: u64) -> Result<(), String> {
+    if tree.size(dir) != size {
+        return Err(format!(
+            "{} has size {}, its files hold {}",
+            tree.path(dir),
+            size,
+            tree.size(dir)
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aoc_7::parse;
+
+    fn puzzle() -> Tree {
+        parse(crate::get_input("resource/aoc_7/data.txt").into_iter())
+    }
+
+    #[test]
+    fn test_json() {
+        let mut tree = Tree::new();
+        let a = tree.add_dir(ROOT, "a").unwrap();
+        tree.add_file(a, "b.txt", 12).unwrap();
+
+        let json = tree.to_json();
+        let expected = r#"{
+  "type": "dir",
+  "name": "/",
+  "size": 12,
+  "children": [
+    {
+      "type": "dir",
+      "name": "a",
+      "size": 12,
+      "children": [
+        {
+          "type": "file",
+          "name": "b.txt",
+          "size": 12
+        }
+      ]
+    }
+  ]
+}"#;
+        assert_eq!(expected, json);
+        assert_eq!(
+            tree.to_string(),
+            Tree::from_json(&json).unwrap().to_string()
+        );
+
+        let tree = puzzle();
+        let loaded = Tree::from_json(&tree.to_json()).unwrap();
+        assert_eq!(tree.to_string(), loaded.to_string());
+        assert_eq!(tree.entry(ROOT), loaded.entry(ROOT));
+    }
+
+    #[test]
+    fn test_invalid_json() {
+        let invalid = [
+            r#"{"type": "dir", "name": "/", "size": 2, "children": []}"#,
+            r#"{"type": "file", "name": "/", "size": 0}"#,
+            r#"{"type": "dir", "name": "x", "size": 0, "children": []}"#,
+            r#"{"type": "dir", "name": "/", "size": 2, "children": [
+                {"type": "file", "name": "a", "size": 1},
+                {"type": "file", "name": "a", "size": 1}]}"#,
+            r#"{"type": "link", "name": "/"}"#,
+            "[",
+        ]
+        .into_iter()
+        .map(str::to_string)
+        .chain(["", ".", "..", "a/b", "a\nb"].iter().map(|name| {
+            format!(
+                r#"{{"type": "dir", "name": "/", "size": 0, "children": [
+                    {{"type": "file", "name": {:?}, "size": 0}}]}}"#,
+                name
+            )
+        }))
+        .chain(["..", "a/b"].iter().map(|name| {
+            format!(
+                r#"{{"type": "dir", "name": "/", "size": 0, "children": [
+                    {{"type": "dir", "name": {:?}, "size": 0, "children": []}}]}}"#,
+                name
+            )
+        }));
+
+        for json in invalid {
+            assert!(Tree::from_json(&json).is_err(), "{}", json);
+        }
+    }
+
+    #[test]
+    fn test_drawing() {
+        let tree = puzzle();
+        let drawing = tree.to_string();
+
+        let loaded = Tree::from_drawing(drawing.lines()).unwrap();
+        assert_eq!(drawing, loaded.to_string());
+
+        let drawing = [
+            "- / (dir, size=3)",
+            "  - a b (dir, size=3)",
+            "    - (c) (file, size=1)",
+            "    - d (dir, size=2)",
+            "      - e (file, size=2)",
+            "    - f (dir, size=0)",
+        ];
+        let loaded = Tree::from_drawing(drawing.into_iter()).unwrap();
+        assert_eq!(
+            drawing.map(|l| l.to_string() + "\n").concat(),
+            loaded.to_string()
+        );
+        assert_eq!(Ok(2), loaded.lookup("/a b/d/e").map(|e| loaded.size(e)));
+    }
+
+    #[test]
+    fn test_invalid_drawing() {
+        let invalid: [&[&str]; 8] = [
+            &[],
+            &["- a (dir, size=0)"],
+            &["- / (dir, size=1)", "    - a (file, size=1)"],
+            &[
+                "- / (dir, size=1)",
+                "  - a (file, size=1)",
+                "    - b (file, size=0)",
+            ],
+            &["- / (dir, size=2)", "  - a (file, size=1)"],
+            &["- / (dir, size=0)", " - a (dir, size=0)"],
+            &["- / (dir, size=0)", "  - .. (dir, size=0)"],
+            &["- / (dir, size=1)", "  - a/b (file, size=1)"],
+        ];
+
+        for drawing in invalid {
+            assert!(
+                Tree::from_drawing(drawing.iter().copied()).is_err(),
+                "{:?}",
+                drawing
+            );
+        }
+    }
+}
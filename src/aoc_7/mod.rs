@@ -1,272 +1,435 @@
-use lazy_static::lazy_static;
-use regex::Regex;
-use std::{
-    cell::RefCell,
-    fmt::Display,
-    rc::{Rc, Weak},
-};
+use std::{collections::HashMap, fmt::Display};
 use TreeNode::{File, Folder};
 
-use crate::get_input;
+pub mod builder;
+pub mod export;
+pub mod query;
+pub mod shell;
+pub mod transcript;
 
-type TreeRef = Rc<RefCell<Tree>>;
-type WeakTreeRef = Weak<RefCell<Tree>>;
+use crate::{get_input, runner::Solution};
 
-#[derive(Debug)]
-struct Tree {
-    size: u64,
+use self::{
+    builder::{build, Policy},
+    query::{Disk, SizePredicate},
+};
+
+/// Position of a node in the tree arena. Once its node is removed,
+/// the position can be given to a new node.
+pub type NodeId = usize;
+
+/// The `/` folder, always there
+pub const ROOT: NodeId = 0;
+
+#[derive(Debug, Clone)]
+struct Node {
     name: String,
+    /// Total size of the files below, for a folder
+    size: u64,
+    parent: Option<NodeId>,
     node_type: TreeNode,
-    parent: Option<WeakTreeRef>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 enum TreeNode {
-    Folder { children: Vec<TreeRef> },
+    Folder {
+        /// In the order they were added
+        children: Vec<NodeId>,
+        by_name: HashMap<String, NodeId>,
+    },
     File,
 }
 
+/// Filesystem tree stored in an arena: nodes refer to their parent and children by
+/// position, and the size of every folder is kept up to date on each change.
+#[derive(Debug, Clone)]
+pub struct Tree {
+    nodes: Vec<Node>,
+    /// Positions of removed nodes, reused first
+    free: Vec<NodeId>,
+}
+
+impl Default for Tree {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Tree {
-    fn parent(node: TreeRef) -> TreeRef {
-        match &node.as_ref().borrow().parent {
-            Some(p) => p.upgrade().unwrap(),
-            None => node.clone(),
+    pub fn new() -> Self {
+        Tree {
+            nodes: vec![Node {
+                name: "/".to_string(),
+                size: 0,
+                parent: None,
+                node_type: Tree::folder(),
+            }],
+            free: vec![],
         }
     }
 
-    fn is_dir(&self) -> bool {
-        if let Folder { children: _ } = self.node_type {
-            true
-        } else {
-            false
+    fn folder() -> TreeNode {
+        Folder {
+            children: vec![],
+            by_name: HashMap::new(),
         }
     }
 
-    fn add_child(&mut self, tree: TreeRef) {
-        match &mut self.node_type {
-            Folder { children } => children.push(tree),
-            _ => unreachable!(),
-        }
+    /// Number of files and folders, the root included
+    pub fn len(&self) -> usize {
+        self.nodes.len() - self.free.len()
     }
 
-    fn find_child_folder(&self, folder_name: &str) -> Option<TreeRef> {
-        match &self.node_type {
-            Folder { children } => Tree::find_in_children(children, folder_name),
-            _ => unreachable!(),
-        }
+    pub fn is_empty(&self) -> bool {
+        self.len() == 1
     }
 
-    fn find_in_children(children: &Vec<TreeRef>, folder_name: &str) -> Option<TreeRef> {
-        children
-            .iter() // iterate over children, easy
-            .filter(|c| {
-                let c = c.borrow();
-                c.is_dir() && c.name == folder_name
-            })
-            .map(|c| c.clone()) //we send back a reference to the child but want also to keep the reference inside the tree > clone the rc
-            .next() // should be only one result, so iter.next should give it
-    }
-
-    fn compute_size(tree: TreeRef) -> u64 {
-        let mut update = false;
-        let compute = match &tree.borrow().node_type {
-            Folder { children } => {
-                update = true;
-                children
-                    .iter()
-                    .fold(0, |acc, c| acc + Tree::compute_size(c.clone()))
-            }
-            File => tree.borrow().size,
-        };
+    pub fn name(&self, node: NodeId) -> &str {
+        &self.nodes[node].name
+    }
+
+    pub fn size(&self, node: NodeId) -> u64 {
+        self.nodes[node].size
+    }
+
+    pub fn is_dir(&self, node: NodeId) -> bool {
+        matches!(self.nodes[node].node_type, Folder { .. })
+    }
+
+    /// `None` for the root
+    pub fn parent(&self, node: NodeId) -> Option<NodeId> {
+        self.nodes[node].parent
+    }
 
-        if update {
-            tree.borrow_mut().size = compute;
+    /// Empty for a file
+    pub fn children(&self, node: NodeId) -> &[NodeId] {
+        match &self.nodes[node].node_type {
+            Folder { children, .. } => children,
+            File => &[],
         }
+    }
 
-        compute
+    pub fn child(&self, node: NodeId, name: &str) -> Option<NodeId> {
+        match &self.nodes[node].node_type {
+            Folder { by_name, .. } => by_name.get(name).copied(),
+            File => None,
+        }
     }
 
-    fn print_with_indent(
-        &self,
-        f: &mut std::fmt::Formatter<'_>,
-        indent: usize,
-    ) -> std::fmt::Result {
-        let Tree {
-            name,
+    pub fn add_dir(&mut self, dir: NodeId, name: &str) -> Result<NodeId, String> {
+        self.add(dir, name, Tree::folder(), 0)
+    }
+
+    pub fn add_file(&mut self, dir: NodeId, name: &str, size: u64) -> Result<NodeId, String> {
+        self.add(dir, name, File, size)
+    }
+
+    fn add(
+        &mut self,
+        dir: NodeId,
+        name: &str,
+        node_type: TreeNode,
+        size: u64,
+    ) -> Result<NodeId, String> {
+        self.check_free_name(dir, name)?;
+
+        let node = Node {
+            name: name.to_string(),
             size,
-            node_type: _,
-            parent: _,
-        } = self;
-        match &self.node_type {
-            Folder { children } => {
-                write!(f, "{:indent$}- {} (dir, size={})\n", "", name, size)?;
-                for child in children {
-                    let child = child.borrow();
-                    child.print_with_indent(f, indent + 2)?;
-                }
-                Ok(())
+            parent: None,
+            node_type,
+        };
+        let id = match self.free.pop() {
+            Some(id) => {
+                self.nodes[id] = node;
+                id
             }
-            File => {
-                write!(f, "{:indent$}- {} (file, size={})\n", "", name, size)
+            None => {
+                self.nodes.push(node);
+                self.nodes.len() - 1
             }
-        }
+        };
+
+        self.attach(dir, id);
+        Ok(id)
     }
 
-    fn visit<F: FnMut(TreeRef)>(tree: TreeRef, f: &mut F) {
-        f(tree.clone());
-        match &tree.borrow().node_type {
-            Folder { children } => {
-                for c in children {
-                    Self::visit(c.clone(), f);
-                }
+    /// Adds an entry listed by `ls`, `dir <name>` or `<size> <name>`.
+    /// An entry already there is kept, with the listed size for a file.
+    pub fn add_entry(&mut self, dir: NodeId, line: &str) -> Result<NodeId, String> {
+        let (name, size) = parse_listing(line)?;
+
+        match (self.child(dir, name), size) {
+            (None, None) => self.add_dir(dir, name),
+            (None, Some(size)) => self.add_file(dir, name, size),
+            (Some(existing), None) if self.is_dir(existing) => Ok(existing),
+            (Some(existing), Some(size)) if !self.is_dir(existing) => {
+                self.set_size(existing, size)?;
+                Ok(existing)
             }
-            _ => (),
+            (Some(_), _) => Err(format!("{} is listed as a file and a folder", name)),
         }
     }
 
-    fn find_total_aoc_7_1(tree: TreeRef) -> u64 {
-        let mut total_folder = 0;
+    pub fn set_size(&mut self, file: NodeId, size: u64) -> Result<(), String> {
+        if self.is_dir(file) {
+            return Err(format!("{} is a folder", self.path(file)));
+        }
 
-        let mut visitor = |t: TreeRef| {
-            let t = t.borrow();
+        let delta = size as i64 - self.nodes[file].size as i64;
+        self.nodes[file].size = size;
+        if let Some(parent) = self.parent(file) {
+            self.update_sizes(parent, delta);
+        }
+        Ok(())
+    }
 
-            if t.is_dir() && t.size <= 100000 {
-                println!("found folder {}", t.name);
-                total_folder += t.size;
-            }
-        };
+    /// Removes a node and everything below it
+    pub fn remove(&mut self, node: NodeId) -> Result<(), String> {
+        if node == ROOT {
+            return Err("Cannot remove /".to_string());
+        }
 
-        Tree::visit(tree, &mut visitor);
-        total_folder
+        self.detach(node);
+        let removed: Vec<NodeId> = self.descendants(node).collect();
+        for id in removed {
+            self.nodes[id].node_type = File;
+            self.free.push(id);
+        }
+        Ok(())
     }
 
-    fn find_min_dir_aoc_7_2(tree: TreeRef) -> u64 {
-        let available = available_space(tree.clone());
+    /// Moves a node in a folder, under a new name
+    pub fn move_to(&mut self, node: NodeId, dir: NodeId, name: &str) -> Result<(), String> {
+        if node == ROOT {
+            return Err("Cannot move /".to_string());
+        }
+        if self.is_within(dir, node) {
+            return Err(format!("Cannot move {} inside itself", self.path(node)));
+        }
+        if self.child(dir, name) != Some(node) {
+            self.check_free_name(dir, name)?;
+        }
 
-        let target = 30000000 - available;
+        self.detach(node);
+        self.nodes[node].name = name.to_string();
+        self.attach(dir, node);
+        Ok(())
+    }
 
-        assert!(target > 0);
+    /// Copies a node and everything below it in a folder, under a new name
+    pub fn copy(&mut self, node: NodeId, dir: NodeId, name: &str) -> Result<NodeId, String> {
+        self.check_free_name(dir, name)?;
+
+        //listed before copying, since the copy can land below the node
+        let originals: Vec<NodeId> = self.descendants(node).collect();
+        let mut copies: HashMap<NodeId, NodeId> = HashMap::new();
+        for original in originals {
+            let (parent, name) = if original == node {
+                (dir, name.to_string())
+            } else {
+                let parent = self.nodes[original].parent.unwrap();
+                (copies[&parent], self.nodes[original].name.clone())
+            };
+            let copy = match self.nodes[original].node_type {
+                Folder { .. } => self.add_dir(parent, &name)?,
+                File => self.add_file(parent, &name, self.nodes[original].size)?,
+            };
+            copies.insert(original, copy);
+        }
 
-        let mut min_dir_size = u64::MAX;
-        let mut visitor = |t: TreeRef| {
-            let t = t.borrow();
-            if t.is_dir() {
-                if t.size >= target && t.size < min_dir_size {
-                    min_dir_size = t.size;
-                }
-            }
-        };
+        Ok(copies[&node])
+    }
+
+    fn check_free_name(&self, dir: NodeId, name: &str) -> Result<(), String> {
+        if !self.is_dir(dir) {
+            return Err(format!("{} is not a folder", self.path(dir)));
+        }
+        if self.child(dir, name).is_some() {
+            return Err(format!("{} already holds {}", self.path(dir), name));
+        }
+        Ok(())
+    }
 
-        Tree::visit(tree, &mut visitor);
+    fn attach(&mut self, dir: NodeId, node: NodeId) {
+        let name = self.nodes[node].name.clone();
+        if let Folder { children, by_name } = &mut self.nodes[dir].node_type {
+            children.push(node);
+            by_name.insert(name, node);
+        }
+        self.nodes[node].parent = Some(dir);
+        self.update_sizes(dir, self.nodes[node].size as i64);
+    }
 
-        min_dir_size
+    fn detach(&mut self, node: NodeId) {
+        if let Some(dir) = self.nodes[node].parent.take() {
+            let name = self.nodes[node].name.clone();
+            if let Folder { children, by_name } = &mut self.nodes[dir].node_type {
+                children.retain(|c| *c != node);
+                by_name.remove(&name);
+            }
+            self.update_sizes(dir, -(self.nodes[node].size as i64));
+        }
     }
-}
 
-impl Display for Tree {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        self.print_with_indent(f, 0)
+    /// Adds `delta` to the size of a folder and all the folders above it
+    fn update_sizes(&mut self, dir: NodeId, delta: i64) {
+        let mut current = Some(dir);
+        while let Some(id) = current {
+            self.nodes[id].size = self.nodes[id].size.wrapping_add_signed(delta);
+            current = self.nodes[id].parent;
+        }
     }
-}
 
-fn parse<T: Iterator<Item = String>>(input: T) -> TreeRef {
-    lazy_static! {
-        static ref CD_REX: Regex = Regex::new(r"\$ cd (?P<folder>[a-zA-Z\.]+)").unwrap();
+    /// Is `node` the `ancestor` or below it
+    pub fn is_within(&self, node: NodeId, ancestor: NodeId) -> bool {
+        let mut current = Some(node);
+        while let Some(id) = current {
+            if id == ancestor {
+                return true;
+            }
+            current = self.nodes[id].parent;
+        }
+        false
     }
 
-    let root = Tree {
-        size: 0,
-        name: "/".to_string(),
-        parent: None,
-        node_type: TreeNode::Folder { children: vec![] },
-    };
+    /// Absolute path, `/` for the root
+    pub fn path(&self, node: NodeId) -> String {
+        let mut names = vec![];
+        let mut current = node;
+        while let Some(parent) = self.nodes[current].parent {
+            names.push(self.nodes[current].name.as_str());
+            current = parent;
+        }
+        names.reverse();
 
-    let mut current_node = Rc::new(RefCell::new(root));
+        format!("/{}", names.join("/"))
+    }
 
-    let root_ref = current_node.clone(); //keep a reference to the root so it's never dereferenced
+    /// The node and everything below it, each folder before its children
+    pub fn descendants(&self, node: NodeId) -> impl Iterator<Item = NodeId> + '_ {
+        let mut stack = vec![node];
+        std::iter::from_fn(move || {
+            let id = stack.pop()?;
+            stack.extend(self.children(id).iter().rev());
+            Some(id)
+        })
+    }
 
-    let mut peekable = input.peekable();
+    /// The node drawn as in the puzzle, with everything below it
+    pub fn subtree(&self, node: NodeId) -> Subtree<'_> {
+        Subtree { tree: self, node }
+    }
 
-    while let Some(line) = peekable.next() {
-        match line.as_str() {
-            "$ cd /" => {
-                current_node = root_ref.clone();
-            }
-            "$ cd .." => current_node = Tree::parent(current_node),
-            "$ ls" => {
-                while let Some(next_line) = peekable.peek() {
-                    //look ahead to find if next line is a command or the result of a ls
-                    if next_line.starts_with("$") {
-                        break;
-                    }
-                    let next_line = peekable.next().unwrap(); //advance the iterator
-
-                    let new_node = parse_folder_item(&next_line, current_node.clone());
-                    let mut current_node = current_node.borrow_mut();
-                    current_node.add_child(Rc::new(RefCell::new(new_node)));
+    fn print_with_indent(
+        &self,
+        f: &mut std::fmt::Formatter<'_>,
+        node: NodeId,
+        indent: usize,
+    ) -> std::fmt::Result {
+        let Node { name, size, .. } = &self.nodes[node];
+        match &self.nodes[node].node_type {
+            Folder { children, .. } => {
+                writeln!(f, "{:indent$}- {} (dir, size={})", "", name, size)?;
+                for child in children {
+                    self.print_with_indent(f, *child, indent + 2)?;
                 }
+                Ok(())
             }
-            s if CD_REX.is_match(s) => {
-                let folder_name = &CD_REX.captures(s).unwrap()["folder"];
-                let child = current_node
-                    .borrow()
-                    .find_child_folder(folder_name)
-                    .expect(&format!(
-                        "Cannot find {} in {}",
-                        folder_name,
-                        current_node.borrow()
-                    ));
-                current_node = child;
+            File => {
+                writeln!(f, "{:indent$}- {} (file, size={})", "", name, size)
             }
-            _ => unreachable!(),
         }
     }
 
-    root_ref
+    fn find_total_aoc_7_1(&self) -> u64 {
+        self.folders(&SizePredicate::AtMost(100000))
+            .iter()
+            .map(|id| self.size(*id))
+            .sum()
+    }
+
+    fn find_min_dir_aoc_7_2(&self) -> u64 {
+        let folder = Disk::DEVICE
+            .folder_to_delete(self)
+            .expect("No folder frees enough space");
+
+        self.size(folder)
+    }
+}
+
+pub struct Subtree<'a> {
+    tree: &'a Tree,
+    node: NodeId,
 }
 
-fn parse_folder_item(line: &String, parent: TreeRef) -> Tree {
-    lazy_static! {
-        static ref FILE_REX: Regex =
-            Regex::new(r"(?P<size>[0-9]+) (?P<name>[a-zA-Z0-9\.]+)").unwrap();
-        static ref FOLDER_REX: Regex = Regex::new(r"dir (?P<name>[a-zA-Z0-9]+)").unwrap();
+impl Display for Subtree<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.tree.print_with_indent(f, self.node, 0)
     }
+}
 
-    if let Some(captures) = FOLDER_REX.captures(line) {
-        Tree {
-            name: captures["name"].to_string(),
-            size: 0,
-            parent: Some(Rc::<RefCell<Tree>>::downgrade(&parent)),
-            node_type: TreeNode::Folder { children: vec![] },
-        }
-    } else if let Some(captures) = FILE_REX.captures(line) {
-        Tree {
-            name: captures["name"].to_string(),
-            size: captures["size"].parse().unwrap(),
-            parent: Some(Rc::<RefCell<Tree>>::downgrade(&parent)),
-            node_type: TreeNode::File,
-        }
-    } else {
-        unreachable!()
+impl Display for Tree {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.print_with_indent(f, ROOT, 0)
+    }
+}
+
+/// Name and size of a line listed by `ls`, no size for a folder
+pub fn parse_listing(line: &str) -> Result<(&str, Option<u64>), String> {
+    let (kind, name) = line
+        .split_once(' ')
+        .ok_or_else(|| format!("Listing format is not correct {}", line))?;
+    let size = match kind {
+        "dir" => None,
+        size => Some(
+            size.parse()
+                .map_err(|_| format!("Listing format is not correct {}", line))?,
+        ),
+    };
+    Ok((name, size))
+}
+
+/// Builds the tree of a transcript, listings of the same folder are merged
+pub fn parse<T: Iterator<Item = String>>(input: T) -> Tree {
+    build(input, Policy::Merge)
+        .unwrap_or_else(|e| panic!("{}", e))
+        .tree
+}
+
+pub(crate) struct Part1;
+pub(crate) struct Part2;
+
+impl Solution for Part1 {
+    type Parsed = Tree;
+    type Output = u64;
+
+    fn parse(&self, input: Vec<String>) -> Self::Parsed {
+        parse(input.into_iter())
+    }
+
+    fn solve(&self, tree: Self::Parsed) -> Self::Output {
+        tree.find_total_aoc_7_1()
     }
 }
 
-fn available_space(tree: TreeRef) -> u64 {
-    let tree = tree.borrow();
+impl Solution for Part2 {
+    type Parsed = Tree;
+    type Output = u64;
 
-    assert!(&tree.parent.is_none());
+    fn parse(&self, input: Vec<String>) -> Self::Parsed {
+        parse(input.into_iter())
+    }
 
-    70000000 - tree.size
+    fn solve(&self, tree: Self::Parsed) -> Self::Output {
+        tree.find_min_dir_aoc_7_2()
+    }
 }
 
 pub fn aoc_7_1() {
     let input = get_input("resource/aoc_7/data.txt");
 
-    let tree = parse(input.into_iter());
-
-    Tree::compute_size(tree.clone());
-
-    let total_folder = Tree::find_total_aoc_7_1(tree);
+    let total_folder = Part1.solve(Part1.parse(input));
 
     println!("AOC 7-1 total folder under 100000 {}", &total_folder);
 }
@@ -274,17 +437,14 @@ pub fn aoc_7_1() {
 pub fn aoc_7_2() {
     let input = get_input("resource/aoc_7/data.txt");
 
-    let tree = parse(input.into_iter());
-
-    Tree::compute_size(tree.clone());
-
-    let min_dir = Tree::find_min_dir_aoc_7_2(tree);
+    let min_dir = Part2.solve(Part2.parse(input));
 
     println!("AOC 7-2 min dir size {}", &min_dir);
 }
 
 #[cfg(test)]
 mod test {
+    use crate::test::Bencher;
     use itertools::Itertools;
 
     use super::*;
@@ -315,40 +475,13 @@ mod test {
         "7214296 k",
     ];
 
-    #[test]
-    fn test_parse() {
-        let iter = INPUT.iter().map(|s| s.to_string());
-
-        let tree = parse(iter);
-
-        let expected = vec![
-            "- / (dir, size=0)",
-            "  - a (dir, size=0)",
-            "    - e (dir, size=0)",
-            "      - i (file, size=584)",
-            "    - f (file, size=29116)",
-            "    - g (file, size=2557)",
-            "    - h.lst (file, size=62596)",
-            "  - b.txt (file, size=14848514)",
-            "  - c.dat (file, size=8504156)",
-            "  - d (dir, size=0)",
-            "    - j (file, size=4060174)",
-            "    - d.log (file, size=8033020)",
-            "    - d.ext (file, size=5626152)",
-            "    - k (file, size=7214296)\n",
-        ]
-        .iter()
-        .join("\n");
-        assert_eq!(tree.borrow().to_string(), expected);
+    fn tree() -> Tree {
+        parse(INPUT.iter().map(|s| s.to_string()))
     }
 
     #[test]
-    fn test_compute() {
-        let iter = INPUT.iter().map(|s| s.to_string());
-
-        let tree = parse(iter);
-
-        Tree::compute_size(tree.clone());
+    fn test_parse() {
+        let tree = tree();
 
         let expected = vec![
             "- / (dir, size=48381165)",
@@ -368,31 +501,77 @@ mod test {
         ]
         .iter()
         .join("\n");
-        assert_eq!(tree.borrow().to_string(), expected);
+        assert_eq!(tree.to_string(), expected);
     }
 
     #[test]
-    fn test_total_size() {
-        let iter = INPUT.iter().map(|s| s.to_string());
-
-        let tree = parse(iter);
+    fn test_sizes_on_change() {
+        let mut tree = tree();
+        let a = tree.child(ROOT, "a").unwrap();
+        let d = tree.child(ROOT, "d").unwrap();
+        let e = tree.child(a, "e").unwrap();
+
+        tree.set_size(tree.child(e, "i").unwrap(), 1000).unwrap();
+        assert_eq!(
+            (1000, 95269, 48381581),
+            (tree.size(e), tree.size(a), tree.size(ROOT))
+        );
+
+        tree.move_to(e, d, "e2").unwrap();
+        assert_eq!((94269, 24934642), (tree.size(a), tree.size(d)));
+        assert_eq!("/d/e2/i", tree.path(tree.child(e, "i").unwrap()));
+
+        let copy = tree.copy(d, a, "d").unwrap();
+        assert_eq!(24934642 + 94269, tree.size(a));
+        assert_eq!(Some(copy), tree.child(a, "d"));
+        assert_eq!(5, tree.children(copy).len());
+
+        let len = tree.len();
+        tree.remove(a).unwrap();
+        assert_eq!(48381581 - 94269, tree.size(ROOT));
+        assert_eq!(len - 11, tree.len());
+        assert_eq!(None, tree.child(ROOT, "a"));
+
+        //removed positions are reused
+        let reused = tree.add_file(ROOT, "new", 1).unwrap();
+        assert!(reused < len);
+    }
 
-        Tree::compute_size(tree.clone());
+    #[test]
+    fn test_invalid_changes() {
+        let mut tree = tree();
+        let a = tree.child(ROOT, "a").unwrap();
+        let e = tree.child(a, "e").unwrap();
+        let f = tree.child(a, "f").unwrap();
+
+        assert!(tree.add_dir(ROOT, "a").is_err());
+        assert!(tree.add_file(f, "x", 1).is_err());
+        assert!(tree.move_to(a, e, "a").is_err());
+        assert!(tree.move_to(f, a, "g").is_err());
+        assert!(tree.move_to(ROOT, a, "root").is_err());
+        assert!(tree.remove(ROOT).is_err());
+        assert!(tree.set_size(a, 1).is_err());
+        assert!(tree.add_entry(a, "dir f").is_err());
+        assert!(tree.add_entry(a, "f").is_err());
+        assert_eq!(Ok(e), tree.add_entry(a, "dir e"));
+
+        //copying a folder below itself copies what was there before
+        let copy = tree.copy(a, e, "a").unwrap();
+        assert_eq!(vec![e, f], tree.children(a)[..2].to_vec());
+        assert_eq!(4, tree.children(copy).len());
+        assert_eq!(2 * 94853, tree.size(a));
+    }
 
-        let total = Tree::find_total_aoc_7_1(tree.clone());
+    #[test]
+    fn test_total_size() {
+        let total = tree().find_total_aoc_7_1();
 
         assert_eq!(95437, total);
     }
 
     #[test]
     fn test_min_dir_size() {
-        let iter = INPUT.iter().map(|s| s.to_string());
-
-        let tree = parse(iter);
-
-        Tree::compute_size(tree.clone());
-
-        let total = Tree::find_min_dir_aoc_7_2(tree.clone());
+        let total = tree().find_min_dir_aoc_7_2();
 
         assert_eq!(24933642, total);
     }
@@ -406,4 +585,51 @@ mod test {
     fn test_aoc_7_2() {
         aoc_7_2();
     }
+
+    /// Transcript of a tree of `entries` files and folders, at most 10 per folder
+    fn generate(entries: usize) -> Vec<String> {
+        fn fill(lines: &mut Vec<String>, remaining: &mut usize, depth: usize) {
+            lines.push("$ ls".to_string());
+            let mut folders = vec![];
+            for i in 0..10 {
+                if *remaining == 0 {
+                    break;
+                }
+                *remaining -= 1;
+                if i % 3 == 0 && depth < 12 {
+                    folders.push(format!("d{}", remaining));
+                    lines.push(format!("dir d{}", remaining));
+                } else {
+                    lines.push(format!("{} f{}", *remaining % 100_000 + 1, remaining));
+                }
+            }
+            for folder in folders {
+                lines.push(format!("$ cd {}", folder));
+                fill(lines, remaining, depth + 1);
+                lines.push("$ cd ..".to_string());
+            }
+        }
+
+        let mut lines = vec!["$ cd /".to_string()];
+        let mut remaining = entries;
+        fill(&mut lines, &mut remaining, 0);
+        lines
+    }
+
+    const MILLION: usize = 1_000_000;
+
+    #[bench]
+    fn bench_parse_million(b: &mut Bencher) {
+        let transcript = generate(MILLION);
+
+        b.iter(|| parse(transcript.iter().cloned()));
+    }
+
+    #[bench]
+    fn bench_total_million(b: &mut Bencher) {
+        let tree = parse(generate(MILLION).into_iter());
+        assert_eq!(MILLION + 1, tree.len());
+
+        b.iter(|| tree.find_total_aoc_7_1());
+    }
 }
@@ -0,0 +1,308 @@
+use std::{cmp::Reverse, fmt::Display};
+
+use super::{NodeId, Tree, ROOT};
+
+/// Size of the device disk, and free space an update needs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Disk {
+    pub total: u64,
+    pub required: u64,
+}
+
+impl Disk {
+    /// The communication device of the puzzle
+    pub const DEVICE: Disk = Disk {
+        total: 70_000_000,
+        required: 30_000_000,
+    };
+
+    /// `None` if the files do not fit on the disk
+    pub fn free(&self, tree: &Tree) -> Option<u64> {
+        self.total.checked_sub(tree.size(ROOT))
+    }
+
+    /// Space to free before the update, 0 if there is enough
+    pub fn to_free(&self, tree: &Tree) -> u64 {
+        match self.free(tree) {
+            Some(free) => self.required.saturating_sub(free),
+            None => self.required + tree.size(ROOT) - self.total,
+        }
+    }
+
+    /// Smallest folder freeing enough space once deleted, `None` if even `/` is too small
+    pub fn folder_to_delete(&self, tree: &Tree) -> Option<NodeId> {
+        let to_free = self.to_free(tree);
+        tree.folders(&SizePredicate::AtLeast(to_free))
+            .into_iter()
+            .min_by_key(|id| tree.size(*id))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SizePredicate {
+    AtMost(u64),
+    AtLeast(u64),
+    /// Both bounds included
+    Between(u64, u64),
+}
+
+impl SizePredicate {
+    pub fn matches(&self, size: u64) -> bool {
+        match *self {
+            SizePredicate::AtMost(max) => size <= max,
+            SizePredicate::AtLeast(min) => size >= min,
+            SizePredicate::Between(min, max) => (min..=max).contains(&size),
+        }
+    }
+}
+
+/// `<=100000`, `>=8M` or `1K..2K`
+impl TryFrom<&str> for SizePredicate {
+    type Error = String;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let value = value.trim();
+
+        if let Some(max) = value.strip_prefix("<=") {
+            Ok(SizePredicate::AtMost(parse_size(max)?))
+        } else if let Some(min) = value.strip_prefix(">=") {
+            Ok(SizePredicate::AtLeast(parse_size(min)?))
+        } else if let Some((min, max)) = value.split_once("..") {
+            Ok(SizePredicate::Between(parse_size(min)?, parse_size(max)?))
+        } else {
+            Err(format!("Size predicate format is not correct {}", value))
+        }
+    }
+}
+
+const UNITS: [&str; 5] = ["", "K", "M", "G", "T"];
+
+/// A number of bytes, with an optional `K`, `M`, `G` or `T` suffix in powers of 1024
+pub fn parse_size(s: &str) -> Result<u64, String> {
+    let s = s.trim();
+    let (number, power) = match UNITS[1..].iter().position(|unit| s.ends_with(unit)) {
+        Some(i) => (&s[..s.len() - 1], i as u32 + 1),
+        None => (s, 0),
+    };
+
+    number
+        .parse::<u64>()
+        .ok()
+        .and_then(|n| n.checked_mul(1024u64.pow(power)))
+        .ok_or_else(|| format!("Expect a size get {}", s))
+}
+
+/// Size as shown by `du -h`: rounded up, one decimal under 10 of a unit
+#[allow(clippy::manual_div_ceil)]
+pub fn human(size: u64) -> String {
+    let mut power = 0;
+    while power + 1 < UNITS.len() && size >= 1024u64.pow(power as u32 + 1) {
+        power += 1;
+    }
+    if power == 0 {
+        return size.to_string();
+    }
+
+    let unit = 1024u64.pow(power as u32);
+    if size < 10 * unit {
+        let tenths = (size * 10 + unit - 1) / unit;
+        if tenths < 100 {
+            return format!("{}.{}{}", tenths / 10, tenths % 10, UNITS[power]);
+        }
+    }
+    format!("{}{}", (size + unit - 1) / unit, UNITS[power])
+}
+
+impl Tree {
+    /// Node at an absolute path like `/a/e`
+    pub fn lookup(&self, path: &str) -> Result<NodeId, String> {
+        let relative = path
+            .strip_prefix('/')
+            .ok_or_else(|| format!("{} is not an absolute path", path))?;
+
+        relative
+            .split('/')
+            .filter(|name| !name.is_empty())
+            .try_fold(ROOT, |node, name| {
+                self.child(node, name)
+                    .ok_or_else(|| format!("{}: No such file or directory", path))
+            })
+    }
+
+    /// Folders whose size matches, each before its children
+    pub fn folders(&self, predicate: &SizePredicate) -> Vec<NodeId> {
+        self.descendants(ROOT)
+            .filter(|id| self.is_dir(*id) && predicate.matches(self.size(*id)))
+            .collect()
+    }
+
+    /// At most `n` folders, the largest first, `/` included
+    pub fn largest_folders(&self, n: usize) -> Vec<NodeId> {
+        self.largest(n, true)
+    }
+
+    pub fn largest_files(&self, n: usize) -> Vec<NodeId> {
+        self.largest(n, false)
+    }
+
+    /// Ties are in tree order
+    fn largest(&self, n: usize, folders: bool) -> Vec<NodeId> {
+        let mut nodes: Vec<NodeId> = self
+            .descendants(ROOT)
+            .filter(|id| self.is_dir(*id) == folders)
+            .collect();
+        nodes.sort_by_key(|id| Reverse(self.size(*id)));
+        nodes.truncate(n);
+        nodes
+    }
+}
+
+/// Report like `du -h`: the size of every folder under a node, children before their parent,
+/// down to `max_depth` levels below the node if given
+pub struct DiskUsage<'a> {
+    pub tree: &'a Tree,
+    pub node: NodeId,
+    pub max_depth: Option<usize>,
+}
+
+impl Display for DiskUsage<'_> {
+    #[allow(clippy::unnecessary_map_or)]
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let tree = self.tree;
+        let mut stack = vec![(self.node, 0, false)];
+
+        while let Some((node, depth, visited)) = stack.pop() {
+            if visited {
+                writeln!(f, "{}\t{}", human(tree.size(node)), tree.path(node))?;
+                continue;
+            }
+
+            stack.push((node, depth, true));
+            if self.max_depth.map_or(true, |max| depth < max) {
+                let folders = tree.children(node).iter().filter(|c| tree.is_dir(**c));
+                stack.extend(folders.rev().map(|c| (*c, depth + 1, false)));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aoc_7::parse;
+
+    fn tree() -> Tree {
+        parse(
+            crate::get_input("resource/aoc_7/session.txt")
+                .into_iter()
+                .take(23),
+        )
+    }
+
+    fn paths(tree: &Tree, nodes: &[NodeId]) -> Vec<String> {
+        nodes.iter().map(|id| tree.path(*id)).collect()
+    }
+
+    #[test]
+    fn test_lookup() {
+        let tree = tree();
+
+        assert_eq!(Ok(ROOT), tree.lookup("/"));
+        assert_eq!(584, tree.size(tree.lookup("/a/e/i").unwrap()));
+        assert_eq!(94853, tree.size(tree.lookup("/a/").unwrap()));
+        assert!(tree.lookup("/a/x").is_err());
+        assert!(tree.lookup("a/e").is_err());
+        assert!(tree.lookup("/b.txt/x").is_err());
+    }
+
+    #[test]
+    fn test_predicates() {
+        let tree = tree();
+        let folders = |predicate: &str| {
+            paths(
+                &tree,
+                &tree.folders(&SizePredicate::try_from(predicate).unwrap()),
+            )
+        };
+
+        assert_eq!(vec!["/a", "/a/e"], folders("<=100000"));
+        assert_eq!(vec!["/", "/d"], folders(">=20M"));
+        assert_eq!(vec!["/a"], folders("1K..100K"));
+        assert!(SizePredicate::try_from("100").is_err());
+        assert!(SizePredicate::try_from("<=x").is_err());
+        assert_eq!(Ok(3 * 1024 * 1024), parse_size("3M"));
+    }
+
+    #[test]
+    fn test_largest() {
+        let tree = tree();
+
+        assert_eq!(vec!["/", "/d"], paths(&tree, &tree.largest_folders(2)));
+        assert_eq!(
+            vec!["/b.txt", "/c.dat", "/d/d.log"],
+            paths(&tree, &tree.largest_files(3))
+        );
+        assert_eq!(4, tree.largest_folders(10).len());
+    }
+
+    #[test]
+    fn test_disk() {
+        let tree = tree();
+
+        assert_eq!(Some(21618835), Disk::DEVICE.free(&tree));
+        assert_eq!(8381165, Disk::DEVICE.to_free(&tree));
+        let folder = Disk::DEVICE.folder_to_delete(&tree).unwrap();
+        assert_eq!("/d", tree.path(folder));
+
+        let small = Disk {
+            total: 40_000_000,
+            required: 30_000_000,
+        };
+        assert_eq!(None, small.free(&tree));
+        assert_eq!(38381165, small.to_free(&tree));
+        assert_eq!(Some(ROOT), small.folder_to_delete(&tree));
+
+        let impossible = Disk {
+            total: 50_000_000,
+            required: 60_000_000,
+        };
+        assert_eq!(None, impossible.folder_to_delete(&tree));
+
+        let roomy = Disk {
+            total: 100_000_000,
+            required: 1,
+        };
+        assert_eq!(0, roomy.to_free(&tree));
+    }
+
+    #[test]
+    fn test_human() {
+        assert_eq!("584", human(584));
+        assert_eq!("1.0K", human(1024));
+        assert_eq!("1.1K", human(1025));
+        assert_eq!("93K", human(94853));
+        assert_eq!("10K", human(10 * 1024 - 1));
+        assert_eq!("24M", human(24933642));
+        assert_eq!("47M", human(48381165));
+    }
+
+    #[test]
+    fn test_disk_usage() {
+        let tree = tree();
+        let report = DiskUsage {
+            tree: &tree,
+            node: ROOT,
+            max_depth: None,
+        };
+        assert_eq!("584\t/a/e\n93K\t/a\n24M\t/d\n47M\t/\n", report.to_string());
+
+        let report = DiskUsage {
+            tree: &tree,
+            node: tree.lookup("/a").unwrap(),
+            max_depth: Some(0),
+        };
+        assert_eq!("93K\t/a\n", report.to_string());
+    }
+}
@@ -0,0 +1,426 @@
+use super::{query::DiskUsage, NodeId, Tree, ROOT};
+
+/// Virtual filesystem driven by terminal commands, in the transcript format.
+/// Paths are absolute from `/` or relative to the current directory, with `.` and `..`.
+#[derive(Default)]
+pub struct Shell {
+    tree: Tree,
+    cwd: NodeId,
+}
+
+impl Shell {
+    pub fn new() -> Self {
+        Shell {
+            tree: Tree::new(),
+            cwd: ROOT,
+        }
+    }
+
+    pub fn tree(&self) -> &Tree {
+        &self.tree
+    }
+
+    /// Runs every command of a transcript and returns their output.
+    /// Lines after `$ ls` are the listed entries and are added to the listed directory,
+    /// lines after any other command are its recorded output and are skipped.
+    pub fn replay<I: Iterator<Item = String>>(&mut self, lines: I) -> Result<Vec<String>, String> {
+        let mut output = vec![];
+        let mut listed: Option<NodeId> = None;
+
+        for (i, line) in lines.enumerate() {
+            let result = match line.strip_prefix("$ ") {
+                Some(command) => match command.split_whitespace().collect::<Vec<_>>()[..] {
+                    ["ls"] => {
+                        listed = Some(self.cwd);
+                        Ok(vec![])
+                    }
+                    ["ls", path] => self.resolve(path).map(|dir| {
+                        listed = Some(dir);
+                        vec![]
+                    }),
+                    _ => {
+                        listed = None;
+                        self.execute(command)
+                    }
+                },
+                None => match listed {
+                    Some(dir) if !line.is_empty() => {
+                        self.tree.add_entry(dir, &line).map(|_| vec![])
+                    }
+                    _ => Ok(vec![]),
+                },
+            };
+
+            output.extend(result.map_err(|e| format!("line {}: {}", i + 1, e))?);
+        }
+
+        Ok(output)
+    }
+
+    /// Runs one command, without the `$ ` prompt, and returns its output
+    pub fn execute(&mut self, command: &str) -> Result<Vec<String>, String> {
+        let words: Vec<&str> = command.split_whitespace().collect();
+
+        match words[..] {
+            ["cd", path] => {
+                let dir = self.resolve(path)?;
+                if !self.tree.is_dir(dir) {
+                    return Err(format!("cd: {}: Not a directory", path));
+                }
+                self.cwd = dir;
+                Ok(vec![])
+            }
+            ["ls"] => Ok(self.listing(self.cwd)),
+            ["ls", path] => Ok(self.listing(self.resolve(path)?)),
+            ["mkdir", path] => {
+                let (dir, name) = self.split(path)?;
+                if self.tree.child(dir, &name).is_some() {
+                    return Err(format!("mkdir: {}: File exists", path));
+                }
+                self.tree.add_dir(dir, &name)?;
+                Ok(vec![])
+            }
+            ["touch", size, path] => {
+                let size = size
+                    .parse()
+                    .map_err(|_| format!("touch: {}: invalid size", size))?;
+                let (dir, name) = self.split(path)?;
+                match self.tree.child(dir, &name) {
+                    Some(node) if self.tree.is_dir(node) => {
+                        return Err(format!("touch: {}: Is a directory", path))
+                    }
+                    Some(node) => self.tree.set_size(node, size)?,
+                    None => {
+                        self.tree.add_file(dir, &name, size)?;
+                    }
+                }
+                Ok(vec![])
+            }
+            ["rm", path] => {
+                let node = self.movable(path)?;
+                if self.tree.is_within(self.cwd, node) {
+                    return Err(format!("rm: {}: holds the current directory", path));
+                }
+                self.tree.remove(node)?;
+                Ok(vec![])
+            }
+            ["mv", source, destination] => {
+                let node = self.movable(source)?;
+                let (dir, name) = self.destination(node, destination)?;
+                if self.tree.is_within(dir, node) {
+                    return Err(format!("mv: cannot move {} inside itself", source));
+                }
+                self.tree.move_to(node, dir, &name)?;
+                Ok(vec![])
+            }
+            ["cp", source, destination] => {
+                let node = self.resolve(source)?;
+                let (dir, name) = self.destination(node, destination)?;
+                self.tree.copy(node, dir, &name)?;
+                Ok(vec![])
+            }
+            ["pwd"] => Ok(vec![self.pwd()]),
+            ["du"] => Ok(self.du(self.cwd)),
+            ["du", "-h"] => Ok(self.du_h(self.cwd)),
+            ["du", "-h", path] => Ok(self.du_h(self.resolve(path)?)),
+            ["du", path] => Ok(self.du(self.resolve(path)?)),
+            ["find"] => Ok(self.find(self.cwd, "*")),
+            ["find", path] => Ok(self.find(self.resolve(path)?, "*")),
+            ["find", path, "-name", pattern] => Ok(self.find(self.resolve(path)?, pattern)),
+            ["tree"] => Ok(self.draw(self.cwd)),
+            ["tree", path] => Ok(self.draw(self.resolve(path)?)),
+            _ => Err(format!("Unknown command {}", command)),
+        }
+    }
+
+    pub fn pwd(&self) -> String {
+        self.tree.path(self.cwd)
+    }
+
+    /// Total size of the files under a path
+    pub fn size(&self, path: &str) -> Result<u64, String> {
+        Ok(self.tree.size(self.resolve(path)?))
+    }
+
+    fn resolve(&self, path: &str) -> Result<NodeId, String> {
+        let mut node = if path.starts_with('/') {
+            ROOT
+        } else {
+            self.cwd
+        };
+
+        for part in path.split('/').filter(|p| !p.is_empty() && *p != ".") {
+            node = match part {
+                ".." => self.tree.parent(node).unwrap_or(ROOT),
+                name if self.tree.is_dir(node) => self
+                    .tree
+                    .child(node, name)
+                    .ok_or_else(|| format!("{}: No such file or directory", path))?,
+                _ => return Err(format!("{}: Not a directory", path)),
+            };
+        }
+
+        Ok(node)
+    }
+
+    /// Existing directory and new name for a path to create
+    fn split(&self, path: &str) -> Result<(NodeId, String), String> {
+        let trimmed = path.trim_end_matches('/');
+        let (dir, name) = match trimmed.rsplit_once('/') {
+            Some(("", name)) => ("/", name),
+            Some((dir, name)) => (dir, name),
+            None => (".", trimmed),
+        };
+        if name.is_empty() || name == "." || name == ".." {
+            return Err(format!("{}: Invalid name", path));
+        }
+
+        let dir = self.resolve(dir)?;
+        if !self.tree.is_dir(dir) {
+            return Err(format!("{}: Not a directory", path));
+        }
+
+        Ok((dir, name.to_string()))
+    }
+
+    /// Any node but the root
+    fn movable(&self, path: &str) -> Result<NodeId, String> {
+        let node = self.resolve(path)?;
+        if node == ROOT {
+            return Err("Cannot move or remove /".to_string());
+        }
+        Ok(node)
+    }
+
+    /// Directory and name `node` gets when moved or copied to `path`: inside `path` if it is
+    /// a directory, replacing it if it is a file and `node` is one too
+    fn destination(&mut self, node: NodeId, path: &str) -> Result<(NodeId, String), String> {
+        let (dir, name) = match self.resolve(path) {
+            Ok(dir) if self.tree.is_dir(dir) => (dir, self.tree.name(node).to_string()),
+            _ => self.split(path)?,
+        };
+
+        if let Some(existing) = self.tree.child(dir, &name) {
+            if existing == node {
+                return Err(format!("{}: Same file", path));
+            }
+            if self.tree.is_dir(existing) || self.tree.is_dir(node) {
+                return Err(format!("{}/{}: File exists", path, name));
+            }
+            self.tree.remove(existing)?;
+        }
+
+        Ok((dir, name))
+    }
+
+    /// Entries of a directory, in the same format as the listing
+    fn listing(&self, dir: NodeId) -> Vec<String> {
+        self.tree
+            .children(dir)
+            .iter()
+            .map(|c| match self.tree.is_dir(*c) {
+                true => format!("dir {}", self.tree.name(*c)),
+                false => format!("{} {}", self.tree.size(*c), self.tree.name(*c)),
+            })
+            .collect()
+    }
+
+    /// `size path` for every directory under `node`, children before their parent
+    fn du(&self, node: NodeId) -> Vec<String> {
+        let mut lines = vec![];
+        let mut stack = vec![(node, false)];
+        while let Some((node, visited)) = stack.pop() {
+            if !self.tree.is_dir(node) {
+                continue;
+            }
+            if visited {
+                lines.push(format!("{} {}", self.tree.size(node), self.tree.path(node)));
+            } else {
+                stack.push((node, true));
+                stack.extend(self.tree.children(node).iter().rev().map(|c| (*c, false)));
+            }
+        }
+        lines
+    }
+
+    fn du_h(&self, node: NodeId) -> Vec<String> {
+        let report = DiskUsage {
+            tree: &self.tree,
+            node,
+            max_depth: None,
+        };
+        report.to_string().lines().map(String::from).collect()
+    }
+
+    /// Paths under `node`, itself included, whose name matches a pattern with `*` and `?`
+    fn find(&self, node: NodeId, pattern: &str) -> Vec<String> {
+        self.tree
+            .descendants(node)
+            .filter(|n| matches(pattern.as_bytes(), self.tree.name(*n).as_bytes()))
+            .map(|n| self.tree.path(n))
+            .collect()
+    }
+
+    fn draw(&self, node: NodeId) -> Vec<String> {
+        self.tree
+            .subtree(node)
+            .to_string()
+            .lines()
+            .map(String::from)
+            .collect()
+    }
+}
+
+fn matches(pattern: &[u8], name: &[u8]) -> bool {
+    match (pattern.first(), name.first()) {
+        (None, None) => true,
+        (Some(b'*'), _) => {
+            matches(&pattern[1..], name) || (!name.is_empty() && matches(pattern, &name[1..]))
+        }
+        (Some(b'?'), Some(_)) => matches(&pattern[1..], &name[1..]),
+        (Some(p), Some(n)) if p == n => matches(&pattern[1..], &name[1..]),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{aoc_7::parse, stream_input};
+
+    fn session(path: &str) -> (Shell, Vec<String>) {
+        let mut shell = Shell::new();
+        let output = shell
+            .replay(stream_input(path).unwrap())
+            .unwrap_or_else(|e| panic!("{}", e));
+        (shell, output)
+    }
+
+    fn run(shell: &mut Shell, commands: &[&str]) -> Vec<String> {
+        commands
+            .iter()
+            .flat_map(|c| shell.execute(c).unwrap_or_else(|e| panic!("{}", e)))
+            .collect()
+    }
+
+    #[test]
+    fn test_replay_puzzle() {
+        let (shell, output) = session("resource/aoc_7/data.txt");
+        assert!(output.is_empty());
+
+        let tree = parse(crate::get_input("resource/aoc_7/data.txt").into_iter());
+        assert_eq!(tree.to_string(), shell.tree().to_string());
+    }
+
+    #[test]
+    fn test_session() {
+        let (shell, output) = session("resource/aoc_7/session.txt");
+
+        let expected = [
+            "/a/e",
+            "584 /a/e",
+            "94853 /a",
+            "/a/h.lst",
+            "/d/d.log",
+            "/e/h.lst",
+            "- e (dir, size=63180)",
+            "  - i (file, size=584)",
+            "  - h.lst (file, size=62596)",
+        ];
+        assert_eq!(expected.to_vec(), output);
+        assert_eq!("/", shell.pwd());
+        assert_eq!(Ok(48381165 + 62596), shell.size("/"));
+    }
+
+    #[test]
+    fn test_commands() {
+        let mut shell = Shell::new();
+
+        let output = run(
+            &mut shell,
+            &[
+                "mkdir a",
+                "mkdir a/b",
+                "touch 10 a/b/x",
+                "touch 20 /a/y",
+                "cd a/b",
+                "cp x ../z",
+                "touch 5 x",
+                "pwd",
+                "cd ../..",
+                "mv a/y a/b",
+                "mv a c",
+                "ls c",
+                "du",
+                "du -h /c",
+            ],
+        );
+
+        let expected = [
+            "/a/b", "dir b", "10 z", "25 /c/b", "35 /c", "35 /", "25\t/c/b", "35\t/c",
+        ];
+        assert_eq!(expected.to_vec(), output);
+
+        run(&mut shell, &["rm c/b", "mkdir c/b"]);
+        assert_eq!(vec!["10 z", "dir b"], run(&mut shell, &["ls /c"]));
+        assert_eq!(Ok(10), shell.size("/"));
+    }
+
+    #[test]
+    fn test_errors() {
+        let mut shell = Shell::new();
+        run(&mut shell, &["mkdir a", "mkdir a/b", "touch 1 f"]);
+
+        for command in [
+            "cd f",
+            "cd x",
+            "mkdir a",
+            "touch 1 a",
+            "touch x g",
+            "rm /",
+            "mv a a/b",
+            "mv f a/b/../..",
+            "cp a f",
+            "du f/g",
+            "format /",
+        ] {
+            assert!(shell.execute(command).is_err(), "{}", command);
+        }
+
+        run(&mut shell, &["cd a/b"]);
+        assert!(shell.execute("rm /a").is_err());
+
+        let error = Shell::new()
+            .replay(
+                ["$ cd /", "$ ls", "dir a", "$ cd b"]
+                    .iter()
+                    .map(|s| s.to_string()),
+            )
+            .unwrap_err();
+        assert_eq!("line 4: b: No such file or directory", error);
+    }
+
+    #[test]
+    fn test_find() {
+        let mut shell = Shell::new();
+        run(
+            &mut shell,
+            &[
+                "mkdir a",
+                "touch 1 a/x.txt",
+                "touch 2 a/y.log",
+                "touch 3 z.txt",
+            ],
+        );
+
+        assert_eq!(
+            vec!["/", "/a", "/a/x.txt", "/a/y.log", "/z.txt"],
+            run(&mut shell, &["find /"])
+        );
+        assert_eq!(
+            vec!["/a/x.txt", "/z.txt"],
+            run(&mut shell, &["find / -name *.txt"])
+        );
+        assert_eq!(vec!["/a/y.log"], run(&mut shell, &["find a -name ?.l*"]));
+    }
+}
@@ -0,0 +1,348 @@
+use std::{
+    collections::VecDeque,
+    fs,
+    io::{self, ErrorKind},
+    path::Path,
+};
+
+use super::{parse, NodeId, Tree, ROOT};
+
+/// Order the folders are visited in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Traversal {
+    /// Into each folder and back out with `cd ..`
+    DepthFirst,
+    /// Level by level, going back to `/` before each folder
+    BreadthFirst,
+}
+
+/// Order of the entries in a folder, listed and visited
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sorting {
+    /// As read from the disk or added to the tree
+    Unsorted,
+    Name,
+    LargestFirst,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Options {
+    pub traversal: Traversal,
+    pub sorting: Sorting,
+    /// Times each folder is listed in a row, at least once
+    pub listings: usize,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            traversal: Traversal::DepthFirst,
+            sorting: Sorting::Unsorted,
+            listings: 1,
+        }
+    }
+}
+
+/// Snapshot of a local directory as a tree, symbolic links are skipped
+pub fn read_dir<P: AsRef<Path>>(path: P) -> io::Result<Tree> {
+    let mut tree = Tree::new();
+    read_into(&mut tree, ROOT, path.as_ref())?;
+    Ok(tree)
+}
+
+fn read_into(tree: &mut Tree, dir: NodeId, path: &Path) -> io::Result<()> {
+    for entry in fs::read_dir(path)? {
+        let entry = entry?;
+        let file_type = entry.file_type()?;
+        let name = entry_name(&entry)?;
+
+        if file_type.is_dir() {
+            let child = tree.add_dir(dir, &name).map_err(invalid)?;
+            read_into(tree, child, &entry.path())?;
+        } else if file_type.is_file() {
+            let size = entry.metadata()?.len();
+            tree.add_file(dir, &name, size).map_err(invalid)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Names must fit on one transcript line
+fn entry_name(entry: &fs::DirEntry) -> io::Result<String> {
+    match entry.file_name().into_string() {
+        Ok(name) if !name.contains('\n') => Ok(name),
+        _ => Err(invalid(format!(
+            "{} cannot be written in a transcript",
+            entry.path().display()
+        ))),
+    }
+}
+
+fn invalid(message: String) -> io::Error {
+    io::Error::new(ErrorKind::InvalidData, message)
+}
+
+/// Terminal session listing every folder of the tree, in the puzzle format
+pub fn transcript(tree: &Tree, options: &Options) -> Vec<String> {
+    let mut lines = vec![];
+
+    match options.traversal {
+        Traversal::DepthFirst => {
+            lines.push("$ cd /".to_string());
+            depth_first(tree, ROOT, options, &mut lines);
+        }
+        Traversal::BreadthFirst => {
+            let mut queue = VecDeque::from([ROOT]);
+            while let Some(dir) = queue.pop_front() {
+                lines.push("$ cd /".to_string());
+                lines.extend(ancestors(tree, dir).map(|n| format!("$ cd {}", tree.name(n))));
+                list(tree, dir, options, &mut lines);
+                queue.extend(folders(tree, dir, options));
+            }
+        }
+    }
+
+    lines
+}
+
+fn depth_first(tree: &Tree, dir: NodeId, options: &Options, lines: &mut Vec<String>) {
+    list(tree, dir, options, lines);
+
+    for child in folders(tree, dir, options) {
+        lines.push(format!("$ cd {}", tree.name(child)));
+        depth_first(tree, child, options, lines);
+        lines.push("$ cd ..".to_string());
+    }
+}
+
+fn list(tree: &Tree, dir: NodeId, options: &Options, lines: &mut Vec<String>) {
+    for _ in 0..options.listings.max(1) {
+        lines.push("$ ls".to_string());
+        for child in sorted(tree, dir, options.sorting) {
+            lines.push(if tree.is_dir(child) {
+                format!("dir {}", tree.name(child))
+            } else {
+                format!("{} {}", tree.size(child), tree.name(child))
+            });
+        }
+    }
+}
+
+fn sorted(tree: &Tree, dir: NodeId, sorting: Sorting) -> Vec<NodeId> {
+    let mut children = tree.children(dir).to_vec();
+    match sorting {
+        Sorting::Unsorted => (),
+        Sorting::Name => children.sort_by_key(|c| tree.name(*c)),
+        Sorting::LargestFirst => children.sort_by_key(|c| std::cmp::Reverse(tree.size(*c))),
+    }
+    children
+}
+
+fn folders(tree: &Tree, dir: NodeId, options: &Options) -> Vec<NodeId> {
+    let mut children = sorted(tree, dir, options.sorting);
+    children.retain(|c| tree.is_dir(*c));
+    children
+}
+
+/// Folders from below the root down to `node` included
+fn ancestors(tree: &Tree, node: NodeId) -> impl Iterator<Item = NodeId> {
+    let mut path = vec![];
+    let mut current = node;
+    while let Some(parent) = tree.parent(current) {
+        path.push(current);
+        current = parent;
+    }
+    path.into_iter().rev()
+}
+
+/// Parses back the transcript of a local directory and compares the size of every folder
+/// with the one computed on the disk. Returns the number of folders checked.
+pub fn check<P: AsRef<Path>>(path: P, options: &Options) -> Result<usize, String> {
+    let snapshot = read_dir(&path).map_err(|e| e.to_string())?;
+    let parsed = parse(transcript(&snapshot, options).into_iter());
+
+    let mut on_disk = vec![];
+    disk_sizes(path.as_ref(), "", &mut on_disk).map_err(|e| e.to_string())?;
+
+    let parsed_folders = parsed.descendants(ROOT).filter(|n| parsed.is_dir(*n));
+    if parsed_folders.count() != on_disk.len() {
+        return Err(format!(
+            "{} folders on the disk, not the same number parsed",
+            on_disk.len()
+        ));
+    }
+
+    for (folder, size) in on_disk.iter() {
+        let path = if folder.is_empty() { "/" } else { folder };
+        let found = parsed.lookup(path)?;
+        if parsed.size(found) != *size {
+            return Err(format!(
+                "{} holds {} bytes on the disk, {} parsed",
+                path,
+                size,
+                parsed.size(found)
+            ));
+        }
+    }
+
+    Ok(on_disk.len())
+}
+
+/// Size of a folder read from the disk, with the size of every folder below it
+fn disk_sizes(path: &Path, tree_path: &str, sizes: &mut Vec<(String, u64)>) -> io::Result<u64> {
+    let mut total = 0;
+    for entry in fs::read_dir(path)? {
+        let entry = entry?;
+        let file_type = entry.file_type()?;
+
+        if file_type.is_dir() {
+            let child_path = format!("{}/{}", tree_path, entry_name(&entry)?);
+            total += disk_sizes(&entry.path(), &child_path, sizes)?;
+        } else if file_type.is_file() {
+            total += entry.metadata()?.len();
+        }
+    }
+
+    sizes.push((tree_path.to_string(), total));
+    Ok(total)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+
+    /// Directory removed at the end of the test, paths ending with `/` are folders
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new(name: &str, entries: &[(&str, usize)]) -> Self {
+            let root = std::env::temp_dir().join(format!("aoc_7_{}_{}", name, std::process::id()));
+            let _ = fs::remove_dir_all(&root);
+            for (path, size) in entries {
+                let full = root.join(path);
+                if path.ends_with('/') {
+                    fs::create_dir_all(&full).unwrap();
+                } else {
+                    fs::create_dir_all(full.parent().unwrap()).unwrap();
+                    fs::write(&full, vec![b'x'; *size]).unwrap();
+                }
+            }
+            TempDir(root)
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    const FILES: [(&str, usize); 7] = [
+        ("a/e/i", 584),
+        ("a/f", 2911),
+        ("a/h.lst", 625),
+        ("b.txt", 1484),
+        ("d/j", 406),
+        ("d/with space.log", 803),
+        ("d/empty/", 0),
+    ];
+
+    fn all_options() -> Vec<Options> {
+        let mut all = vec![];
+        for traversal in [Traversal::DepthFirst, Traversal::BreadthFirst] {
+            for sorting in [Sorting::Unsorted, Sorting::Name, Sorting::LargestFirst] {
+                for listings in [1, 3] {
+                    all.push(Options {
+                        traversal,
+                        sorting,
+                        listings,
+                    });
+                }
+            }
+        }
+        all
+    }
+
+    #[test]
+    fn test_check_directory() {
+        let dir = TempDir::new("check", &FILES);
+
+        for options in all_options() {
+            assert_eq!(Ok(5), check(&dir.0, &options), "{:?}", options);
+        }
+
+        let tree = read_dir(&dir.0).unwrap();
+        assert_eq!(
+            584 + 2911 + 625 + 1484 + 406 + 803,
+            tree.size(ROOT) as usize
+        );
+        assert_eq!(0, tree.size(tree.lookup("/d/empty").unwrap()));
+    }
+
+    #[test]
+    fn test_transcript_formats() {
+        let tree = parse(
+            crate::get_input("resource/aoc_7/session.txt")
+                .into_iter()
+                .take(23),
+        );
+
+        let options = Options {
+            traversal: Traversal::BreadthFirst,
+            sorting: Sorting::Name,
+            listings: 2,
+        };
+        let lines = transcript(&tree, &options);
+        let expected = [
+            "$ cd /",
+            "$ ls",
+            "dir a",
+            "14848514 b.txt",
+            "8504156 c.dat",
+            "dir d",
+            "$ ls",
+            "dir a",
+            "14848514 b.txt",
+            "8504156 c.dat",
+            "dir d",
+            "$ cd /",
+            "$ cd a",
+            "$ ls",
+            "dir e",
+            "29116 f",
+            "2557 g",
+            "62596 h.lst",
+            "$ ls",
+            "dir e",
+            "29116 f",
+            "2557 g",
+            "62596 h.lst",
+            "$ cd /",
+            "$ cd d",
+            "$ ls",
+            "5626152 d.ext",
+            "8033020 d.log",
+            "4060174 j",
+            "7214296 k",
+            "$ ls",
+        ];
+        assert_eq!(expected.to_vec(), lines[..expected.len()].to_vec());
+
+        //the puzzle transcript is a depth first one, in the order it lists the entries
+        let mut puzzle: Vec<String> = crate::get_input("resource/aoc_7/session.txt")
+            .into_iter()
+            .take(23)
+            .collect();
+        puzzle.push("$ cd ..".to_string());
+        assert_eq!(puzzle, transcript(&tree, &Options::default()));
+
+        for options in all_options() {
+            let parsed = parse(transcript(&tree, &options).into_iter());
+            assert_eq!(tree.size(ROOT), parsed.size(ROOT));
+            assert_eq!(tree.len(), parsed.len());
+        }
+    }
+}
@@ -1,66 +1,25 @@
 use std::fmt::Display;
 use Direction::{East, North, South, West};
 
-use crate::get_input;
+use crate::{get_input, runner::Solution};
 
 type TreeSize = i32;
-type ScenicDistance = u32;
-type ScenicScore = u32;
+type ScenicScore = u64;
 
-struct ElvenMap {
+pub(crate) struct ElvenMap {
     map: Vec<Vec<TreeSize>>,
     max_col: usize,
     max_row: usize,
 }
+/// What every tree sees, looking in the four directions
 #[derive(Debug, Clone, PartialEq, Eq)]
-struct MaxByDirection {
-    north: Option<TreeSize>,
-    south: Option<TreeSize>,
-    west: Option<TreeSize>,
-    east: Option<TreeSize>,
+struct Views {
+    /// Product of the viewing distances of each tree
+    scores: Vec<Vec<ScenicScore>>,
+    /// Whether each tree can be seen from outside the grid
+    visible: Vec<Vec<bool>>,
 }
 
-impl MaxByDirection {
-    fn new() -> Self {
-        MaxByDirection {
-            north: None,
-            south: None,
-            west: None,
-            east: None,
-        }
-    }
-
-    fn update(&mut self, dir: &Direction, value: TreeSize) {
-        match dir {
-            North => self.north = Some(value),
-            South => self.south = Some(value),
-            East => self.east = Some(value),
-            West => self.west = Some(value),
-        }
-    }
-
-    fn get_direction(&self, dir: &Direction) -> Option<TreeSize> {
-        match dir {
-            North => self.north,
-            South => self.south,
-            East => self.east,
-            West => self.west,
-        }
-    }
-
-    fn check_visible(&self, tree_size: TreeSize) -> bool {
-        let (n, s, w, e) = (
-            self.north.unwrap(),
-            self.south.unwrap(),
-            self.west.unwrap(),
-            self.east.unwrap(),
-        );
-        tree_size > n || tree_size > s || tree_size > w || tree_size > e
-    }
-}
-
-type MaxMap = Vec<Vec<MaxByDirection>>;
-
 impl ElvenMap {
     fn new<I: Iterator<Item = String>>(input: I) -> ElvenMap {
         let mut result = vec![];
@@ -83,124 +42,79 @@ impl ElvenMap {
         }
     }
 
-    fn make_max_map(&self) -> MaxMap {
-        let mut max_map: MaxMap = vec![vec![MaxByDirection::new(); self.max_col]; self.max_row];
-
-        for row in 0..self.max_row {
-            for col in 0..self.max_col {
-                //left to right up to down > do north and west
-                Self::update(&mut max_map, row, col, self, &Direction::North);
-                Self::update(&mut max_map, row, col, self, &Direction::West);
-
-                //right to left and down to up > do south and east
-                let reverse_row = self.max_row - 1 - row;
-                let reverse_col = self.max_col - 1 - col;
-                Self::update(
-                    &mut max_map,
-                    reverse_row,
-                    reverse_col,
-                    self,
-                    &Direction::South,
-                );
-                Self::update(
-                    &mut max_map,
-                    reverse_row,
-                    reverse_col,
-                    self,
-                    &Direction::East,
-                );
+    /// Every line is scanned once with a stack of the trees still in view, tallest at the
+    /// bottom: the trees lower than the current one are hidden behind it from then on.
+    #[allow(clippy::unnecessary_map_or)]
+    fn views(&self) -> Views {
+        let mut scores = vec![vec![1; self.max_col]; self.max_row];
+        let mut visible = vec![vec![false; self.max_col]; self.max_row];
+
+        for dir in [North, South, West, East] {
+            for line in self.lines(&dir) {
+                //position along the line and height, heights never increasing
+                let mut stack: Vec<(usize, TreeSize)> = vec![];
+
+                for (i, &(row, col)) in line.iter().enumerate() {
+                    let height = self.map[row][col];
+                    while stack.last().map_or(false, |(_, h)| *h < height) {
+                        stack.pop();
+                    }
+
+                    let distance = match stack.last() {
+                        Some((blocking, _)) => i - blocking,
+                        None => {
+                            visible[row][col] = true;
+                            i
+                        }
+                    };
+                    scores[row][col] *= distance as ScenicScore;
+                    stack.push((i, height));
+                }
             }
         }
 
-        max_map
+        Views { scores, visible }
     }
 
-    fn update(max_map: &mut MaxMap, row: usize, col: usize, elf_map: &ElvenMap, dir: &Direction) {
-        if max_map[row][col].get_direction(dir).is_none() {
-            let updated_max = if elf_map.get_boundary_check(dir)(row, col) {
-                TreeSize::MIN
-            } else {
-                let (t_row, t_col) = dir.move_coordinates(row, col);
-                let previous_max = max_map[t_row][t_col].get_direction(dir);
-                assert!(
-                    previous_max.is_some(),
-                    "not is some {} {} {:?}",
-                    t_row,
-                    t_col,
-                    &dir
-                );
-                let previous_max = previous_max.unwrap();
-                let adjacent_value = elf_map.map[t_row][t_col];
-
-                adjacent_value.max(previous_max)
-            };
-
-            max_map[row][col].update(dir, updated_max);
-        }
-    }
-
-    fn get_boundary_check<'a>(&'a self, dir: &Direction) -> Box<dyn Fn(usize, usize) -> bool + 'a> {
+    /// Coordinates of every row or column, each starting at the edge `dir` looks toward
+    fn lines(&self, dir: &Direction) -> Vec<Vec<(usize, usize)>> {
+        let (rows, cols) = (0..self.max_row, 0..self.max_col);
         match dir {
-            North => Box::new(|row, _col| row == 0),
-            South => Box::new(|row, _col| row == self.max_row - 1),
-            West => Box::new(|_row, col| col == 0),
-            East => Box::new(|_row, col| col == self.max_col - 1),
+            North => cols
+                .map(|c| rows.clone().map(|r| (r, c)).collect())
+                .collect(),
+            South => cols
+                .map(|c| rows.clone().rev().map(|r| (r, c)).collect())
+                .collect(),
+            West => rows
+                .map(|r| cols.clone().map(|c| (r, c)).collect())
+                .collect(),
+            East => rows
+                .map(|r| cols.clone().rev().map(|c| (r, c)).collect())
+                .collect(),
         }
     }
 
     fn count_visible(&self) -> usize {
-        let max_map = self.make_max_map();
-
-        self.map
+        self.views()
+            .visible
             .iter()
-            .zip(max_map.iter())
-            .fold(0, |acc, (heights, maxes)| {
-                acc + heights
-                    .iter()
-                    .zip(maxes.iter())
-                    .filter(|(height, max)| max.check_visible(**height))
-                    .count() //counts visible trees in one row
-            })
-    }
-
-    fn calculate_scenic_distance(&self, row: usize, col: usize, dir: Direction) -> ScenicDistance {
-        let height = self.map[row][col];
-        let check_bound = self.get_boundary_check(&dir);
-
-        if check_bound(row, col) {
-            0
-        } else {
-            let mut dist = 1;
-            let (mut t_row, mut t_col) = dir.move_coordinates(row, col);
-            while !check_bound(t_row, t_col) && height > self.map[t_row][t_col] {
-                dist += 1;
-                (t_row, t_col) = dir.move_coordinates(t_row, t_col);
-            }
-            dist
-        }
-    }
-
-    fn calculate_scenic_score(&self, row: usize, col: usize) -> ScenicScore {
-        vec![North, South, East, West]
-            .into_iter()
-            .map(|dir| self.calculate_scenic_distance(row, col, dir))
-            .fold(1, |acc, sd| acc * sd)
+            .flatten()
+            .filter(|v| **v)
+            .count()
     }
 
     fn max_scenic_score(&self) -> ScenicScore {
-        let mut max_score = ScenicScore::MIN;
-        for i in 0..self.max_row {
-            for j in 0..self.max_col {
-                let score = self.calculate_scenic_score(i, j);
-                if score > max_score {
-                    max_score = score;
-                }
-            }
-        }
-
-        max_score
+        self.views()
+            .scores
+            .iter()
+            .flatten()
+            .copied()
+            .max()
+            .unwrap_or(ScenicScore::MIN)
     }
 }
+
 #[derive(Debug)]
 enum Direction {
     North,
@@ -209,17 +123,6 @@ enum Direction {
     East,
 }
 
-impl Direction {
-    fn move_coordinates(&self, row: usize, col: usize) -> (usize, usize) {
-        match self {
-            North => (row - 1, col),
-            South => (row + 1, col),
-            East => (row, col + 1),
-            West => (row, col - 1),
-        }
-    }
-}
-
 impl Display for ElvenMap {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let map = &self.map;
@@ -233,11 +136,39 @@ impl Display for ElvenMap {
     }
 }
 
+pub(crate) struct Part1;
+pub(crate) struct Part2;
+
+impl Solution for Part1 {
+    type Parsed = ElvenMap;
+    type Output = usize;
+
+    fn parse(&self, input: Vec<String>) -> Self::Parsed {
+        ElvenMap::new(input.into_iter())
+    }
+
+    fn solve(&self, elven_map: Self::Parsed) -> Self::Output {
+        elven_map.count_visible()
+    }
+}
+
+impl Solution for Part2 {
+    type Parsed = ElvenMap;
+    type Output = ScenicScore;
+
+    fn parse(&self, input: Vec<String>) -> Self::Parsed {
+        ElvenMap::new(input.into_iter())
+    }
+
+    fn solve(&self, elven_map: Self::Parsed) -> Self::Output {
+        elven_map.max_scenic_score()
+    }
+}
+
 pub fn aoc_8_1() {
     let input = get_input("resource/aoc_8/data.txt");
 
-    let elven_map = ElvenMap::new(input.into_iter());
-    let count = elven_map.count_visible();
+    let count = Part1.solve(Part1.parse(input));
 
     println!("AOC-8-1 count visible: {}", &count);
 }
@@ -245,8 +176,7 @@ pub fn aoc_8_1() {
 pub fn aoc_8_2() {
     let input = get_input("resource/aoc_8/data.txt");
 
-    let elven_map = ElvenMap::new(input.into_iter());
-    let max_score = elven_map.max_scenic_score();
+    let max_score = Part2.solve(Part2.parse(input));
 
     println!("AOC-8-2 max score: {}", &max_score);
 }
@@ -275,62 +205,69 @@ mod tests {
     }
 
     #[test]
-    fn test_max_map() {
+    fn test_views() {
         let input = INPUT.iter().map(|s| s.to_string());
 
         let map = ElvenMap::new(input);
 
-        let max_map = map.make_max_map();
-
-        let m = TreeSize::MIN;
-
-        let expected = vec![
-            vec![
-                nswe(m, 6, m, 7),
-                nswe(m, 5, 3, 7),
-                nswe(m, 5, 3, 7),
-                nswe(m, 9, 3, 3),
-                nswe(m, 9, 7, m),
-            ],
-            vec![
-                nswe(3, 6, m, 5),
-                nswe(0, 5, 2, 5),
-                nswe(3, 5, 5, 2),
-                nswe(7, 9, 5, 2),
-                nswe(3, 9, 5, m),
-            ],
-            vec![
-                nswe(3, 3, m, 5),
-                nswe(5, 5, 6, 3),
-                nswe(5, 5, 6, 3),
-                nswe(7, 9, 6, 2),
-                nswe(3, 9, 6, m),
-            ],
-            vec![
-                nswe(6, 3, m, 9),
-                nswe(5, 5, 3, 9),
-                nswe(5, 3, 3, 9),
-                nswe(7, 9, 5, 9),
-                nswe(3, 0, 5, m),
-            ],
-            vec![
-                nswe(6, m, m, 9),
-                nswe(5, m, 3, 9),
-                nswe(5, m, 5, 9),
-                nswe(7, m, 5, 0),
-                nswe(9, m, 9, m),
-            ],
+        let views = map.views();
+
+        let expected_scores = vec![
+            vec![0, 0, 0, 0, 0],
+            vec![0, 1, 4, 1, 0],
+            vec![0, 6, 1, 2, 0],
+            vec![0, 1, 8, 3, 0],
+            vec![0, 0, 0, 0, 0],
         ];
+        assert_eq!(expected_scores, views.scores);
+
+        let (t, f) = (true, false);
+        let expected_visible = vec![
+            vec![t, t, t, t, t],
+            vec![t, t, t, f, t],
+            vec![t, t, f, t, t],
+            vec![t, f, t, f, t],
+            vec![t, t, t, t, t],
+        ];
+        assert_eq!(expected_visible, views.visible);
+    }
 
-        itertools::assert_equal(max_map, expected);
+    /// Score and visibility of a tree by walking outward from it
+    fn walk(map: &ElvenMap, row: usize, col: usize) -> (ScenicScore, bool) {
+        let height = map.map[row][col];
+        let lines: [Vec<TreeSize>; 4] = [
+            (0..row).rev().map(|r| map.map[r][col]).collect(),
+            (row + 1..map.max_row).map(|r| map.map[r][col]).collect(),
+            (0..col).rev().map(|c| map.map[row][c]).collect(),
+            (col + 1..map.max_col).map(|c| map.map[row][c]).collect(),
+        ];
+
+        let score = lines
+            .iter()
+            .map(|line| {
+                let blocking = line.iter().position(|h| *h >= height);
+                blocking.map_or(line.len(), |i| i + 1) as ScenicScore
+            })
+            .product();
+        let visible = lines.iter().any(|line| line.iter().all(|h| *h < height));
+        (score, visible)
     }
 
-    fn nswe(n: TreeSize, s: TreeSize, w: TreeSize, e: TreeSize) -> MaxByDirection {
-        MaxByDirection {
-            north: Some(n),
-            south: Some(s),
-            west: Some(w),
-            east: Some(e),
+    #[test]
+    fn test_views_match_walk() {
+        let map = ElvenMap::new(get_input("resource/aoc_8/data.txt").into_iter());
+        let views = map.views();
+
+        for row in 0..map.max_row {
+            for col in 0..map.max_col {
+                assert_eq!(
+                    walk(&map, row, col),
+                    (views.scores[row][col], views.visible[row][col]),
+                    "{} {}",
+                    row,
+                    col
+                );
+            }
         }
     }
 
@@ -355,19 +292,46 @@ mod tests {
 
         let map = ElvenMap::new(input);
 
-        let sscore = map.calculate_scenic_score(3, 2);
-        assert_eq!(sscore, 8);
-
-        let sscore = map.calculate_scenic_score(1, 2);
-        assert_eq!(sscore, 4);
+        let scores = map.views().scores;
+        assert_eq!(scores[3][2], 8);
+        assert_eq!(scores[1][2], 4);
 
         let max = map.max_scenic_score();
 
         assert_eq!(max, 8);
     }
-    
+
+    #[test]
+    fn test_large_score() {
+        //a single tall tree in the middle of a flat 1000x1000 forest sees every edge
+        let rows = (0..1000).map(|row| {
+            (0..1000)
+                .map(|col| if (row, col) == (500, 500) { '9' } else { '0' })
+                .collect()
+        });
+        let map = ElvenMap::new(rows);
+
+        assert_eq!(500 * 499 * 500 * 499, map.max_scenic_score());
+    }
+
     #[test]
     fn test_aoc_8_2() {
         aoc_8_2()
     }
+
+    /// Square forest of `size` rows, heights in a repeating pattern
+    fn forest(size: usize) -> ElvenMap {
+        let rows = (0..size).map(|row| {
+            (0..size)
+                .map(|col| char::from_digit(((row * 7 + col * 3) % 10) as u32, 10).unwrap())
+                .collect()
+        });
+        ElvenMap::new(rows)
+    }
+
+    #[bench]
+    fn bench_views_1000(b: &mut test::Bencher) {
+        let map = forest(1000);
+        b.iter(|| map.views());
+    }
 }
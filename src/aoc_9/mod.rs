@@ -4,7 +4,10 @@ use lazy_static::lazy_static;
 use regex::Regex;
 use Move::{Down, Left, Right, Up};
 
-use crate::get_input;
+use crate::{
+    runner::{Lines, Solution},
+    stream_input,
+};
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
 struct Position {
@@ -18,7 +21,7 @@ impl Position {
     }
 }
 
-enum Move {
+pub(crate) enum Move {
     Up(i32),
     Down(i32),
     Left(i32),
@@ -138,22 +141,66 @@ fn tail_positions_chain<I: Iterator<Item = Move>>(moves: I) -> usize {
     tail_positions.len()
 }
 
-pub fn aoc_9_1() {
-    let input = get_input("resource/aoc_9/data.txt");
+pub(crate) struct Part1;
+pub(crate) struct Part2;
+
+/// Moves parsed lazily so the input is never held in memory
+pub(crate) type Moves = Box<dyn Iterator<Item = Move>>;
+
+fn parse_moves(lines: Lines) -> Moves {
+    Box::new(lines.map(|s| Into::<Move>::into(s.as_str())))
+}
+
+impl Solution for Part1 {
+    type Parsed = Moves;
+    type Output = usize;
+
+    const STREAMING: bool = true;
+
+    fn parse(&self, input: Vec<String>) -> Self::Parsed {
+        self.parse_lines(Box::new(input.into_iter()))
+    }
+
+    fn parse_lines(&self, lines: Lines) -> Self::Parsed {
+        parse_moves(lines)
+    }
+
+    fn solve(&self, moves: Self::Parsed) -> Self::Output {
+        tail_positions(moves)
+    }
+}
+
+impl Solution for Part2 {
+    type Parsed = Moves;
+    type Output = usize;
+
+    const STREAMING: bool = true;
+
+    fn parse(&self, input: Vec<String>) -> Self::Parsed {
+        self.parse_lines(Box::new(input.into_iter()))
+    }
+
+    fn parse_lines(&self, lines: Lines) -> Self::Parsed {
+        parse_moves(lines)
+    }
+
+    fn solve(&self, moves: Self::Parsed) -> Self::Output {
+        tail_positions_chain(moves)
+    }
+}
 
-    let moves = input.iter().map(|s| Into::<Move>::into(s.as_str()));
+pub fn aoc_9_1() {
+    let input = stream_input("resource/aoc_9/data.txt").expect("Cannot read file");
 
-    let pos_count = tail_positions(moves);
+    let pos_count = Part1.solve(Part1.parse_lines(Box::new(input)));
 
     println!("AOC-9-1 count {}", &pos_count);
 }
 
 pub fn aoc_9_2() {
-    let input = get_input("resource/aoc_9/data.txt");
-
-    let moves = input.iter().map(|s| Into::<Move>::into(s.as_str()));
+    let input = stream_input("resource/aoc_9/data.txt").expect("Cannot read file");
 
-    let pos_count = tail_positions_chain(moves);
+    let pos_count = Part2.solve(Part2.parse_lines(Box::new(input)));
 
     println!("AOC-9-2 count chain {}", &pos_count);
 }
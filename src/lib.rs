@@ -1,7 +1,15 @@
 #![feature(map_first_last)]
 #![feature(mixed_integer_ops)]
+#![cfg_attr(test, feature(test))]
 
-use std::{fs, path::Path};
+#[cfg(test)]
+extern crate test;
+
+use std::{
+    fs::{self, File},
+    io::{self, BufRead, BufReader},
+    path::Path,
+};
 
 pub mod aoc_1;
 pub mod aoc_10;
@@ -18,9 +26,70 @@ pub mod aoc_6;
 pub mod aoc_7;
 pub mod aoc_8;
 pub mod aoc_9;
+pub mod runner;
+pub mod top_k;
+
+fn read_input<T: AsRef<Path> + ?Sized>(path: &T) -> io::Result<Vec<String>> {
+    let data = fs::read_to_string(path)?;
+
+    Ok(data
+        .split('\n')
+        .map(|s| s.strip_suffix('\r').unwrap_or(s).to_string())
+        .collect())
+}
 
 fn get_input<T: AsRef<Path> + ?Sized>(path: &T) -> Vec<String> {
-    let data = fs::read_to_string(path).expect("Cannot read file");
+    read_input(path).expect("Cannot read file")
+}
+
+/// Lines of an input file read lazily, so a day consuming its input line by line
+/// runs in constant memory whatever the file size.
+/// Unlike `get_input`, a trailing line break does not produce a last empty line.
+pub struct InputLines {
+    lines: io::Lines<BufReader<File>>,
+}
+
+impl Iterator for InputLines {
+    type Item = String;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.lines.next().map(|line| {
+            let mut line = line.expect("Cannot read line");
+            if line.ends_with('\r') {
+                line.pop();
+            }
+            line
+        })
+    }
+}
+
+pub fn stream_input<T: AsRef<Path> + ?Sized>(path: &T) -> io::Result<InputLines> {
+    let file = File::open(path)?;
+
+    Ok(InputLines {
+        lines: BufReader::new(file).lines(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs};
+
+    use super::*;
+
+    #[test]
+    fn test_stream_input() {
+        let path = env::temp_dir().join("aoc_test_stream_input.txt");
+        fs::write(&path, "1000\r\n2000\r\n\r\n3000\n").unwrap();
+
+        let lines: Vec<String> = stream_input(&path).unwrap().collect();
+        fs::remove_file(&path).unwrap();
+
+        itertools::assert_equal(lines, vec!["1000", "2000", "", "3000"]);
+    }
 
-    data.split("\r\n").map(|s| s.to_string()).collect()
+    #[test]
+    fn test_stream_missing_input() {
+        assert!(stream_input("resource/aoc_0/data.txt").is_err());
+    }
 }
@@ -1,48 +1,530 @@
-use aoc::aoc_1::*;
-use aoc::aoc_10::*;
-use aoc::aoc_11::*;
-use aoc::aoc_12::*;
-use aoc::aoc_13::*;
-use aoc::aoc_14::*;
-use aoc::aoc_15::*;
-use aoc::aoc_2::*;
-use aoc::aoc_3::*;
-use aoc::aoc_4::*;
-use aoc::aoc_5::*;
-use aoc::aoc_6::*;
-use aoc::aoc_7::*;
-use aoc::aoc_8::*;
-use aoc::aoc_9::*;
+use std::{
+    env,
+    fs::File,
+    io::{self, BufRead, BufReader},
+    panic, process,
+    time::Duration,
+};
+
+use aoc::{
+    aoc_1::inventory::{Inventory, Stats},
+    aoc_2::{
+        mapping::analyze,
+        tournament::{Copycat, FrequencyCounter, Guide, Random, Strategy, Tournament},
+        Game,
+    },
+    aoc_3::{
+        bitset::Alphabet,
+        diagnostics::{Diagnostics, Grouping},
+    },
+    aoc_4::{
+        index::{IntervalTree, Query},
+        population::{Analysis, Population},
+    },
+    aoc_5::{
+        crane::{self, Crane, MoveError},
+        parse_input,
+        planner::{Planner, Target},
+        write_input,
+        yard::Yard,
+    },
+    aoc_6::decoder::Decoder,
+    aoc_7::{
+        builder::{build, Policy},
+        query::{parse_size, Disk, DiskUsage, SizePredicate},
+        shell::Shell,
+        transcript::{self, Sorting, Traversal},
+        NodeId, Tree, ROOT,
+    },
+    runner::{registry::registry, run_all, RunOptions, Summary},
+    stream_input,
+};
+
+const USAGE: &str = "\
+Usage: aoc [--timeout <seconds>]
+       aoc stats <day> [--group <n>] [--sliding] [input file]
+       aoc tournament [--rounds <n>] [--seed <seed>] [--log] [guide file...]
+       aoc query <input file> [<stab|overlaps|containing|within> <section or range>...]
+       aoc yard <input file> [9000|9001|<crates per lift>]
+       aoc plan <initial drawing file> <target drawing file | --tops <crates>> [crane]
+       aoc decode [input file]
+       aoc shell [transcript file]
+       aoc du <transcript file> [--depth n] [--top n] [--match <predicate>] [--disk <total> <required>]
+       aoc transcript <directory> [--bfs] [--sort <name|size>] [--listings n] [--check]
+       aoc tree <transcript, JSON or drawing file> [--json] [--policy merge|last-wins|error]";
+
+/// Prints the error and the usage on stderr, then exits with a failure code
+fn usage_error(message: &str) -> ! {
+    eprintln!("{}\n{}", message, USAGE);
+    process::exit(2)
+}
+
+/// Usage: aoc [--timeout <seconds>]
+fn parse_options(mut args: impl Iterator<Item = String>) -> RunOptions {
+    let mut options = RunOptions::default();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--timeout" => {
+                let timeout = args
+                    .next()
+                    .and_then(|s| s.parse().ok())
+                    .and_then(|seconds| Duration::try_from_secs_f64(seconds).ok())
+                    .filter(|timeout| !timeout.is_zero());
+                match timeout {
+                    Some(timeout) => options.timeout = Some(timeout),
+                    None => usage_error("--timeout expects a positive number of seconds"),
+                }
+            }
+            "--help" | "-h" => {
+                println!("{}", USAGE);
+                process::exit(0)
+            }
+            other => usage_error(&format!("Unknown argument {}", other)),
+        }
+    }
+
+    options
+}
+
+/// Usage: aoc stats <day> [--group <n>] [--sliding] [input file]
+/// Grouping options apply to day 3 badges, groups of 3 consecutive lines by default
+fn stats(args: &[String]) {
+    let day = args.first().expect("stats expects a day");
+    let mut group_size = 3;
+    let mut sliding = false;
+    let mut path = None;
+
+    let mut options = args[1..].iter();
+    while let Some(option) = options.next() {
+        match option.as_str() {
+            "--group" => {
+                group_size = options
+                    .next()
+                    .and_then(|n| n.parse().ok())
+                    .expect("--group expects a number");
+            }
+            "--sliding" => sliding = true,
+            other => path = Some(other.to_string()),
+        }
+    }
+
+    let path = path.unwrap_or_else(|| format!("resource/aoc_{}/data.txt", day));
+    let input = stream_input(&path).expect("Cannot read file");
+
+    match day.as_str() {
+        "1" => {
+            let inventory = Inventory::parse(input);
+            print!(
+                "{}",
+                Stats {
+                    inventory: &inventory,
+                    top: 3
+                }
+            );
+        }
+        "2" => {
+            let analysis = analyze(Game::classic(), input).unwrap_or_else(|e| panic!("{}", e));
+            print!("{}", analysis);
+        }
+        "3" => {
+            let alphabet = Alphabet::classic();
+            let grouping = if sliding {
+                Grouping::Sliding(group_size)
+            } else {
+                Grouping::Fixed(group_size)
+            };
+            let diagnostics =
+                Diagnostics::parse(&alphabet, grouping, input).unwrap_or_else(|e| panic!("{}", e));
+            print!("{}", diagnostics);
+        }
+        "4" => {
+            let population = Population::parse(input).unwrap_or_else(|e| panic!("{}", e));
+            print!("{}", Analysis(&population));
+        }
+        other => panic!("No stats for day {}", other),
+    }
+}
+
+/// Usage: aoc tournament [--rounds <n>] [--seed <seed>] [--log] [guide file...]
+/// Guides play round-robin against each other and the built-in strategies,
+/// the puzzle guide is used if no file is given
+fn tournament(mut args: impl Iterator<Item = String>) {
+    let game = Game::classic();
+    let mut rounds = 1000;
+    let mut seed = 2022;
+    let mut log = false;
+    let mut players: Vec<Box<dyn Strategy>> = vec![];
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--rounds" => {
+                rounds = args
+                    .next()
+                    .and_then(|s| s.parse().ok())
+                    .expect("--rounds expects a number");
+            }
+            "--seed" => {
+                seed = args
+                    .next()
+                    .and_then(|s| s.parse().ok())
+                    .expect("--seed expects a number");
+            }
+            "--log" => log = true,
+            path => players.push(Box::new(
+                Guide::load(game, path).unwrap_or_else(|e| panic!("{}", e)),
+            )),
+        }
+    }
+
+    if players.is_empty() {
+        let guide =
+            Guide::load(game, "resource/aoc_2/data.txt").unwrap_or_else(|e| panic!("{}", e));
+        players.push(Box::new(guide));
+    }
+    players.push(Box::new(FrequencyCounter));
+    players.push(Box::new(Copycat));
+    players.push(Box::new(Random::new(seed)));
+
+    let results = Tournament {
+        game,
+        rounds,
+        players,
+    }
+    .run();
+
+    if log {
+        print!("{}", results.log(game));
+    }
+    print!("{}", results);
+}
+
+/// Usage: aoc query <input file> [<stab|overlaps|containing|within> <section or range>...]
+/// Queries are read from the standard input, one per line, if none is given
+fn query(args: &[String]) {
+    let path = args.first().expect("query expects an input file");
+    let population = Population::parse(stream_input(path).expect("Cannot read file"))
+        .unwrap_or_else(|e| panic!("{}", e));
+    let tree = IntervalTree::new(population.assignments().iter().map(|a| a.interval));
+
+    let answer = |query: &str| match Query::try_from(query) {
+        Ok(query) => {
+            let found = tree.query(&query);
+            println!("{}: {} elves", query, found.len());
+            for a in found.iter().map(|i| &population.assignments()[*i]) {
+                println!("  elf #{} (line {}): {}", a.elf, a.line, a.interval);
+            }
+        }
+        Err(e) => println!("{}", e),
+    };
+
+    if args.len() > 1 {
+        for pair in args[1..].chunks(2) {
+            answer(&pair.join(" "));
+        }
+    } else {
+        for line in io::stdin().lock().lines() {
+            answer(&line.expect("Cannot read query"));
+        }
+    }
+}
+
+fn show_yard(yard: &Yard<dyn Crane>) {
+    println!("After {} of {} moves", yard.position(), yard.len());
+    for line in yard.draw() {
+        println!("{}", line);
+    }
+}
+
+fn yard_command(yard: &mut Yard<dyn Crane>, command: &str) -> Result<(), MoveError> {
+    let words: Vec<&str> = command.split_whitespace().collect();
+    let number = |i: usize| words.get(i).and_then(|w| w.parse::<usize>().ok());
+
+    match (words.first().copied(), number(1), number(2)) {
+        (Some("next"), _, _) => {
+            yard.forward()?;
+            show_yard(yard);
+        }
+        (Some("back"), _, _) => {
+            yard.back();
+            show_yard(yard);
+        }
+        (Some("jump"), Some(n), _) => {
+            yard.jump(n)?;
+            show_yard(yard);
+        }
+        (Some("stack"), Some(stack), _) => println!("{:?}", yard.touching_stack(stack)),
+        (Some("crate"), Some(stack), Some(level)) => {
+            println!("{:?}", yard.touching_crate(stack, level)?)
+        }
+        (Some("history"), _, _) => {
+            for (i, m) in yard.history() {
+                println!("{}: {}", i, m);
+            }
+        }
+        (Some("show"), _, _) => show_yard(yard),
+        _ => println!("Unknown command {}", command),
+    }
+
+    Ok(())
+}
+
+/// Usage: aoc yard <input file> [9000|9001|<crates per lift>]
+/// Steps through the moves with commands read from the standard input:
+/// next, back, jump <n>, stack <n>, crate <stack> <level>, history, show
+fn yard(args: &[String]) {
+    let path = args.first().expect("yard expects an input file");
+    let crane = crane::by_name(args.get(1).map_or("9000", |s| s.as_str()))
+        .unwrap_or_else(|e| panic!("{}", e));
+    let input: Vec<String> = stream_input(path).expect("Cannot read file").collect();
+    let (crates, moves) = parse_input(&input);
+    let mut yard = Yard::new(crane.as_ref(), crates, moves);
+
+    for line in io::stdin().lock().lines() {
+        if let Err(e) = yard_command(&mut yard, &line.expect("Cannot read command")) {
+            print!("{}", e);
+        }
+    }
+}
+
+fn read_crates(path: &str) -> aoc::aoc_5::Crates {
+    let input: Vec<String> = stream_input(path).expect("Cannot read file").collect();
+    parse_input(&input).0
+}
+
+/// Usage: aoc plan <initial drawing file> <target drawing file | --tops <crates>> [crane]
+/// Prints a puzzle input going from the initial stacks to the target
+fn plan(args: &[String]) {
+    let initial = read_crates(args.first().expect("plan expects an initial drawing"));
+    let (target, crane) = match args.get(1).map(|s| s.as_str()) {
+        Some("--tops") => (
+            Target::Tops(args.get(2).expect("--tops expects crates").clone()),
+            args.get(3),
+        ),
+        Some(path) => (Target::Arrangement(read_crates(path)), args.get(2)),
+        None => panic!("plan expects a target"),
+    };
+    let crane =
+        crane::by_name(crane.map_or("9000", |s| s.as_str())).unwrap_or_else(|e| panic!("{}", e));
+
+    let plan = Planner::new(crane.as_ref())
+        .plan(&initial, &target)
+        .unwrap_or_else(|e| panic!("{}", e));
+
+    if !plan.shortest {
+        eprintln!("Search too long, the plan may not be the shortest");
+    }
+    for line in write_input(&initial, &plan.moves) {
+        println!("{}", line);
+    }
+}
+
+/// Usage: aoc decode [input file]
+/// Each line of the file, or of the standard input, is decoded as a datastream
+fn decode(args: &[String]) {
+    let source: Box<dyn BufRead> = match args.first() {
+        Some(path) => Box::new(BufReader::new(File::open(path).expect("Cannot read file"))),
+        None => Box::new(io::stdin().lock()),
+    };
+
+    for stream in Decoder::new(source) {
+        print!("{}", stream.expect("Cannot read datastream"));
+    }
+}
+
+/// Usage: aoc shell [transcript file]
+/// Replays the transcript, then runs the commands read from the standard input
+fn shell(args: &[String]) {
+    let mut shell = Shell::new();
+
+    if let Some(path) = args.first() {
+        let transcript = stream_input(path).expect("Cannot read file");
+        match shell.replay(transcript) {
+            Ok(output) => output.iter().for_each(|line| println!("{}", line)),
+            Err(e) => panic!("{}", e),
+        }
+    }
+
+    for line in io::stdin().lock().lines() {
+        let line = line.expect("Cannot read command");
+        match shell.execute(line.trim_start_matches("$ ")) {
+            Ok(output) => output.iter().for_each(|line| println!("{}", line)),
+            Err(e) => println!("{}", e),
+        }
+    }
+}
+
+fn print_nodes(title: &str, tree: &Tree, nodes: &[NodeId]) {
+    println!("{}:", title);
+    for node in nodes {
+        println!("  {}\t{}", tree.size(*node), tree.path(*node));
+    }
+}
+
+/// Usage: aoc du <transcript file> [--depth n] [--top n] [--match <predicate>]
+/// [--disk <total> <required>]
+/// Prints the size of every folder like `du -h`, then the folder to delete for the update
+fn du(args: &[String]) {
+    let path = args.first().expect("du expects a transcript file");
+    let mut shell = Shell::new();
+    shell
+        .replay(stream_input(path).expect("Cannot read file"))
+        .unwrap_or_else(|e| panic!("{}", e));
+    let tree = shell.tree();
+
+    let size = |arg: Option<&String>| parse_size(arg.expect("Expect a size")).unwrap();
+    let (mut max_depth, mut top, mut predicate, mut disk) = (None, None, None, Disk::DEVICE);
+    let mut options = args[1..].iter();
+    while let Some(option) = options.next() {
+        match option.as_str() {
+            "--depth" => {
+                let depth = options.next().and_then(|n| n.parse().ok());
+                max_depth = Some(depth.expect("--depth expects a number"));
+            }
+            "--top" => {
+                let n = options.next().and_then(|n| n.parse().ok());
+                top = Some(n.expect("--top expects a number"));
+            }
+            "--match" => {
+                let arg = options.next().expect("--match expects a predicate");
+                predicate = Some(SizePredicate::try_from(arg.as_str()).unwrap());
+            }
+            "--disk" => {
+                disk = Disk {
+                    total: size(options.next()),
+                    required: size(options.next()),
+                }
+            }
+            other => panic!("Unknown option {}", other),
+        }
+    }
+
+    let report = DiskUsage {
+        tree,
+        node: ROOT,
+        max_depth,
+    };
+    print!("{}", report);
+
+    if let Some(n) = top {
+        print_nodes("Largest folders", tree, &tree.largest_folders(n));
+        print_nodes("Largest files", tree, &tree.largest_files(n));
+    }
+    if let Some(predicate) = predicate {
+        print_nodes("Matching folders", tree, &tree.folders(&predicate));
+    }
+    println!("To free: {}", disk.to_free(tree));
+    match disk.folder_to_delete(tree) {
+        Some(folder) => println!("Delete: {} ({})", tree.path(folder), tree.size(folder)),
+        None => println!("Delete: no folder frees enough space"),
+    }
+}
+
+/// Usage: aoc transcript <directory> [--bfs] [--sort <name|size>] [--listings n] [--check]
+/// Prints a terminal session listing the directory, or checks it parses back to the same sizes
+fn transcript(args: &[String]) {
+    let path = args.first().expect("transcript expects a directory");
+    let mut options = transcript::Options::default();
+    let mut check = false;
+
+    let mut args = args[1..].iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--bfs" => options.traversal = Traversal::BreadthFirst,
+            "--sort" => {
+                options.sorting = match args.next().map(|s| s.as_str()) {
+                    Some("name") => Sorting::Name,
+                    Some("size") => Sorting::LargestFirst,
+                    other => panic!("Unknown sorting {:?}", other),
+                }
+            }
+            "--listings" => {
+                options.listings = args
+                    .next()
+                    .and_then(|n| n.parse().ok())
+                    .expect("--listings expects a number")
+            }
+            "--check" => check = true,
+            other => panic!("Unknown option {}", other),
+        }
+    }
+
+    if check {
+        match transcript::check(path, &options) {
+            Ok(folders) => println!("Same sizes for {} folders", folders),
+            Err(e) => println!("{}", e),
+        }
+    } else {
+        let tree = transcript::read_dir(path).expect("Cannot read directory");
+        for line in transcript::transcript(&tree, &options) {
+            println!("{}", line);
+        }
+    }
+}
+
+/// Usage: aoc tree <transcript, JSON or drawing file> [--json] [--policy merge|last-wins|error]
+/// Prints the tree drawn as in the puzzle, or in JSON. The anomalies of a transcript are
+/// reported on stderr, resolved by the policy, merge by default.
+fn tree(args: &[String]) {
+    let path = args.first().expect("tree expects a file");
+    let text = std::fs::read_to_string(path).expect("Cannot read file");
+
+    let (mut json, mut policy) = (false, Policy::Merge);
+    let mut options = args[1..].iter();
+    while let Some(option) = options.next() {
+        match option.as_str() {
+            "--json" => json = true,
+            "--policy" => {
+                let arg = options.next().expect("--policy expects a policy");
+                policy = Policy::try_from(arg.as_str()).unwrap_or_else(|e| panic!("{}", e));
+            }
+            other => panic!("Unknown option {}", other),
+        }
+    }
+
+    let tree = match text.trim_start().chars().next() {
+        Some('{') => Tree::from_json(&text),
+        Some('-') => Tree::from_drawing(text.lines()),
+        _ => build(text.lines().map(String::from), policy).map(|built| {
+            for anomaly in built.anomalies.iter() {
+                eprintln!("{}", anomaly);
+            }
+            built.tree
+        }),
+    }
+    .unwrap_or_else(|e| panic!("{}", e));
+
+    if json {
+        println!("{}", tree.to_json());
+    } else {
+        print!("{}", tree);
+    }
+}
+
+fn run(args: impl Iterator<Item = String>) {
+    let options = parse_options(args);
+
+    panic::set_hook(Box::new(|_| {})); //panics are reported in the summary instead
+
+    let reports = run_all(&registry(), &options);
+
+    print!("{}", Summary(&reports));
+}
 
 fn main() {
-    aoc_1_1();
-    aoc_1_2();
-    aoc_2_1();
-    aoc_2_2();
-    aoc_3_1();
-    aoc_3_2();
-    aoc_4_1();
-    aoc_4_2();
-    aoc_5_1();
-    aoc_5_2();
-    aoc_6_1();
-    aoc_6_2();
-    aoc_7_1();
-    aoc_7_2();
-    aoc_8_1();
-    aoc_8_2();
-    aoc_9_1();
-    aoc_9_2();
-    aoc_10_1();
-    aoc_10_2();
-    aoc_11_1();
-    aoc_11_2();
-    aoc_12_1();
-    aoc_12_2();
-    aoc_13_1();
-    aoc_13_2();
-    aoc_14_1();
-    aoc_14_2();
-    aoc_15_1();
-    aoc_15_2();
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    match args.first().map(|s| s.as_str()) {
+        Some("transcript") => transcript(&args[1..]),
+        Some("tree") => tree(&args[1..]),
+        Some("du") => du(&args[1..]),
+        Some("shell") => shell(&args[1..]),
+        Some("stats") => stats(&args[1..]),
+        Some("decode") => decode(&args[1..]),
+        Some("plan") => plan(&args[1..]),
+        Some("yard") => yard(&args[1..]),
+        Some("query") => query(&args[1..]),
+        Some("tournament") => tournament(args.into_iter().skip(1)),
+        _ => run(args.into_iter()),
+    }
 }
@@ -0,0 +1,455 @@
+use std::{
+    any::Any,
+    fmt::Display,
+    panic::{self, AssertUnwindSafe},
+    sync::{mpsc, Arc},
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::stream_input;
+
+pub mod registry;
+
+/// Input handed to a solution by the runner, read lazily from the input file
+pub type Lines = Box<dyn Iterator<Item = String> + Send>;
+
+/// A puzzle part split in two steps so they can be timed separately
+pub trait Solution {
+    type Parsed;
+    type Output: Display;
+
+    /// Set by days overriding `parse_lines` with a lazy parse: the work then happens
+    /// while solving, so both steps are timed as one and reported under solve
+    const STREAMING: bool = false;
+
+    fn parse(&self, input: Vec<String>) -> Self::Parsed;
+
+    /// Days consuming their input line by line override this to keep it lazy,
+    /// by default the whole input is loaded before parsing
+    fn parse_lines(&self, lines: Lines) -> Self::Parsed {
+        self.parse(lines.collect())
+    }
+
+    fn solve(&self, parsed: Self::Parsed) -> Self::Output;
+}
+
+/// Type erased version of a `Solution`, so every day can live in the same registry
+trait Runnable {
+    fn run(&self, input: Lines, timings: &mut Timings) -> String;
+}
+
+impl<S: Solution> Runnable for S {
+    fn run(&self, input: Lines, timings: &mut Timings) -> String {
+        if S::STREAMING {
+            let start = Instant::now();
+            let answer = self.solve(self.parse_lines(input)).to_string();
+            timings.solve = Some(start.elapsed());
+            return answer;
+        }
+
+        let start = Instant::now();
+        let parsed = self.parse_lines(input);
+        timings.parse = Some(start.elapsed());
+
+        let start = Instant::now();
+        let answer = self.solve(parsed).to_string();
+        timings.solve = Some(start.elapsed());
+
+        answer
+    }
+}
+
+#[derive(Clone)]
+pub struct Entry {
+    pub day: u8,
+    pub part: u8,
+    pub expected: Option<&'static str>,
+    solution: Arc<dyn Runnable + Send + Sync>,
+}
+
+impl Entry {
+    pub fn new<S: Solution + Send + Sync + 'static>(
+        day: u8,
+        part: u8,
+        solution: S,
+        expected: Option<&'static str>,
+    ) -> Self {
+        Entry {
+            day,
+            part,
+            expected,
+            solution: Arc::new(solution),
+        }
+    }
+
+    pub fn input_path(&self) -> String {
+        format!("resource/aoc_{}/data.txt", self.day)
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Timings {
+    pub parse: Option<Duration>,
+    pub solve: Option<Duration>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Status {
+    Ok,
+    Unchecked, //no expected answer in the registry
+    Mismatch { expected: String },
+    Error(String),
+    Panicked(String),
+    TimedOut(Duration),
+}
+
+impl Display for Status {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Status::Ok => write!(f, "ok"),
+            Status::Unchecked => write!(f, "unchecked"),
+            Status::Mismatch { expected } => write!(f, "mismatch (expected {})", expected),
+            Status::Error(e) => write!(f, "error: {}", e),
+            Status::Panicked(msg) => write!(f, "panicked: {}", msg),
+            Status::TimedOut(limit) => write!(f, "timed out after {:.2?}", limit),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Report {
+    pub day: u8,
+    pub part: u8,
+    pub answer: Option<String>,
+    pub status: Status,
+    pub timings: Timings,
+}
+
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    if let Some(s) = payload.downcast_ref::<&str>() {
+        s.to_string()
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s.clone()
+    } else {
+        "unknown panic payload".to_string()
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct RunOptions {
+    /// Wall-clock limit for parse + solve of one day/part, no limit if `None`
+    pub timeout: Option<Duration>,
+}
+
+/// Runs one entry against the given input, a panic is turned into a `Status::Panicked`
+fn run_with_input(entry: &Entry, input: Lines) -> Report {
+    let mut timings = Timings::default();
+
+    let result = panic::catch_unwind(AssertUnwindSafe(|| entry.solution.run(input, &mut timings)));
+
+    let (answer, status) = match result {
+        Ok(answer) => {
+            let status = match entry.expected {
+                None => Status::Unchecked,
+                Some(expected) if expected == answer => Status::Ok,
+                Some(expected) => Status::Mismatch {
+                    expected: expected.to_string(),
+                },
+            };
+            (Some(answer), status)
+        }
+        Err(payload) => (None, Status::Panicked(panic_message(payload))),
+    };
+
+    Report {
+        day: entry.day,
+        part: entry.part,
+        answer,
+        status,
+        timings,
+    }
+}
+
+fn failed_report(entry: &Entry, status: Status) -> Report {
+    Report {
+        day: entry.day,
+        part: entry.part,
+        answer: None,
+        status,
+        timings: Timings::default(),
+    }
+}
+
+/// Runs the entry on its own thread so a never ending solve can be abandoned.
+/// A timed out thread is detached and keeps running until the process exits.
+fn run_isolated(entry: &Entry, input: Lines, options: &RunOptions) -> Report {
+    let isolated = entry.clone();
+
+    let (sender, receiver) = mpsc::channel();
+    let spawned = thread::Builder::new()
+        .name(format!("aoc-{}-{}", entry.day, entry.part))
+        .spawn(move || {
+            let report = run_with_input(&isolated, input);
+            let _ = sender.send(report); //the receiver is gone if we timed out
+        });
+
+    if let Err(e) = spawned {
+        return failed_report(entry, Status::Error(format!("cannot spawn thread: {}", e)));
+    }
+
+    let received = match options.timeout {
+        Some(limit) => receiver.recv_timeout(limit).map_err(|e| match e {
+            mpsc::RecvTimeoutError::Timeout => Status::TimedOut(limit),
+            mpsc::RecvTimeoutError::Disconnected => {
+                Status::Panicked("worker thread died".to_string())
+            }
+        }),
+        None => receiver
+            .recv()
+            .map_err(|_| Status::Panicked("worker thread died".to_string())),
+    };
+
+    received.unwrap_or_else(|status| failed_report(entry, status))
+}
+
+pub fn run_entry(entry: &Entry, options: &RunOptions) -> Report {
+    let path = entry.input_path();
+    match stream_input(&path).map_err(|e| format!("cannot read {}: {}", path, e)) {
+        Ok(input) => run_isolated(entry, Box::new(input), options),
+        Err(e) => failed_report(entry, Status::Error(e)),
+    }
+}
+
+pub fn run_all(entries: &[Entry], options: &RunOptions) -> Vec<Report> {
+    entries.iter().map(|e| run_entry(e, options)).collect()
+}
+
+/// End of run table, one line per day/part
+pub struct Summary<'a>(pub &'a [Report]);
+
+fn format_duration(d: Option<Duration>) -> String {
+    d.map_or("-".to_string(), |d| format!("{:.2?}", d))
+}
+
+fn format_answer(answer: &Option<String>) -> String {
+    match answer {
+        None => "-".to_string(),
+        Some(a) if a.contains('\n') => format!("<{} lines>", a.lines().count()),
+        Some(a) => a.clone(),
+    }
+}
+
+impl Display for Summary<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let header = ["Day", "Part", "Answer", "Parse", "Solve", "Status"];
+        let rows: Vec<[String; 6]> = self
+            .0
+            .iter()
+            .map(|r| {
+                [
+                    r.day.to_string(),
+                    r.part.to_string(),
+                    format_answer(&r.answer),
+                    format_duration(r.timings.parse),
+                    format_duration(r.timings.solve),
+                    r.status.to_string(),
+                ]
+            })
+            .collect();
+
+        let mut widths = header.map(|h| h.len());
+        for row in rows.iter() {
+            for (w, cell) in widths.iter_mut().zip(row.iter()) {
+                *w = (*w).max(cell.chars().count());
+            }
+        }
+
+        let header = header.map(|h| h.to_string());
+        for row in std::iter::once(&header).chain(rows.iter()) {
+            let line = row
+                .iter()
+                .zip(widths.iter())
+                .map(|(cell, w)| format!("{:w$}", cell, w = w))
+                .collect::<Vec<String>>()
+                .join(" | ");
+            writeln!(f, "{}", line.trim_end())?;
+        }
+
+        let failed = self
+            .0
+            .iter()
+            .filter(|r| !matches!(r.status, Status::Ok | Status::Unchecked))
+            .count();
+        writeln!(f, "{} run, {} failed", self.0.len(), failed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Sum;
+
+    impl Solution for Sum {
+        type Parsed = Vec<u32>;
+        type Output = u32;
+
+        fn parse(&self, input: Vec<String>) -> Self::Parsed {
+            input.iter().map(|s| s.parse().unwrap()).collect()
+        }
+
+        fn solve(&self, parsed: Self::Parsed) -> Self::Output {
+            parsed.iter().sum()
+        }
+    }
+
+    fn input(lines: &[&str]) -> Lines {
+        let lines: Vec<String> = lines.iter().map(|s| s.to_string()).collect();
+        Box::new(lines.into_iter())
+    }
+
+    #[test]
+    fn test_status() {
+        let ok = Entry::new(1, 1, Sum, Some("6"));
+        let report = run_with_input(&ok, input(&["1", "2", "3"]));
+        assert_eq!(report.status, Status::Ok);
+        assert_eq!(report.answer, Some("6".to_string()));
+        assert!(report.timings.parse.is_some() && report.timings.solve.is_some());
+
+        let mismatch = Entry::new(1, 1, Sum, Some("7"));
+        let report = run_with_input(&mismatch, input(&["1", "2", "3"]));
+        assert_eq!(
+            report.status,
+            Status::Mismatch {
+                expected: "7".to_string()
+            }
+        );
+
+        let unchecked = Entry::new(1, 1, Sum, None);
+        let report = run_with_input(&unchecked, input(&["1"]));
+        assert_eq!(report.status, Status::Unchecked);
+    }
+
+    #[test]
+    fn test_panic_is_reported() {
+        let entry = Entry::new(1, 1, Sum, Some("6"));
+        let report = run_with_input(&entry, input(&["1", "a"]));
+
+        assert_eq!(report.answer, None);
+        assert!(matches!(report.status, Status::Panicked(_)));
+        assert_eq!(report.timings.parse, None);
+    }
+
+    struct StreamingSum;
+
+    impl Solution for StreamingSum {
+        type Parsed = Lines;
+        type Output = u32;
+
+        const STREAMING: bool = true;
+
+        fn parse(&self, input: Vec<String>) -> Self::Parsed {
+            self.parse_lines(Box::new(input.into_iter()))
+        }
+
+        fn parse_lines(&self, lines: Lines) -> Self::Parsed {
+            lines
+        }
+
+        fn solve(&self, parsed: Self::Parsed) -> Self::Output {
+            parsed.map(|s| s.parse::<u32>().unwrap()).sum()
+        }
+    }
+
+    #[test]
+    fn test_streaming_timings() {
+        let entry = Entry::new(1, 1, StreamingSum, Some("6"));
+        let report = run_with_input(&entry, input(&["1", "2", "3"]));
+
+        assert_eq!(report.status, Status::Ok);
+        assert_eq!(report.timings.parse, None);
+        assert!(report.timings.solve.is_some());
+    }
+
+    struct Sleepy;
+
+    impl Solution for Sleepy {
+        type Parsed = ();
+        type Output = u32;
+
+        fn parse(&self, _input: Vec<String>) -> Self::Parsed {}
+
+        fn solve(&self, _parsed: Self::Parsed) -> Self::Output {
+            thread::sleep(Duration::from_secs(2));
+            0
+        }
+    }
+
+    #[test]
+    fn test_timeout() {
+        let entry = Entry::new(1, 1, Sleepy, Some("0"));
+        let options = RunOptions {
+            timeout: Some(Duration::from_millis(50)),
+        };
+
+        let report = run_isolated(&entry, input(&[]), &options);
+        assert_eq!(report.status, Status::TimedOut(Duration::from_millis(50)));
+        assert_eq!(report.answer, None);
+
+        let report = run_isolated(&entry, input(&[]), &RunOptions::default());
+        assert_eq!(report.status, Status::Ok);
+    }
+
+    #[test]
+    fn test_isolated_panic() {
+        let entry = Entry::new(1, 1, Sum, Some("6"));
+        let options = RunOptions {
+            timeout: Some(Duration::from_secs(10)),
+        };
+
+        let report = run_isolated(&entry, input(&["a"]), &options);
+        assert!(matches!(report.status, Status::Panicked(_)));
+
+        let report = run_isolated(&entry, input(&["1", "5"]), &options);
+        assert_eq!(report.status, Status::Ok);
+    }
+
+    #[test]
+    fn test_missing_input() {
+        let entry = Entry::new(99, 1, Sum, None);
+        let report = run_entry(&entry, &RunOptions::default());
+
+        assert!(matches!(report.status, Status::Error(_)));
+    }
+
+    #[test]
+    fn test_summary() {
+        let reports = vec![
+            Report {
+                day: 1,
+                part: 1,
+                answer: Some("6".to_string()),
+                status: Status::Ok,
+                timings: Timings::default(),
+            },
+            Report {
+                day: 10,
+                part: 2,
+                answer: Some("#.\n.#".to_string()),
+                status: Status::Panicked("boom".to_string()),
+                timings: Timings::default(),
+            },
+        ];
+
+        let expected = [
+            "Day | Part | Answer    | Parse | Solve | Status",
+            "1   | 1    | 6         | -     | -     | ok",
+            "10  | 2    | <2 lines> | -     | -     | panicked: boom",
+            "2 run, 1 failed\n",
+        ]
+        .join("\n");
+
+        assert_eq!(Summary(&reports).to_string(), expected);
+    }
+}
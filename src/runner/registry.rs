@@ -0,0 +1,50 @@
+use crate::{
+    aoc_1, aoc_10, aoc_11, aoc_12, aoc_13, aoc_14, aoc_15, aoc_2, aoc_3, aoc_4, aoc_5, aoc_6,
+    aoc_7, aoc_8, aoc_9,
+};
+
+use super::Entry;
+
+const AOC_10_2_SCREEN: &str = "\
+###..#..#..##..####..##....##.###..###..
+#..#.#.#..#..#....#.#..#....#.#..#.#..#.
+#..#.##...#..#...#..#..#....#.###..#..#.
+###..#.#..####..#...####....#.#..#.###..
+#.#..#.#..#..#.#....#..#.#..#.#..#.#.#..
+#..#.#..#.#..#.####.#..#..##..###..#..#.";
+
+/// Every solved day/part with the answer accepted for our input
+pub fn registry() -> Vec<Entry> {
+    vec![
+        Entry::new(1, 1, aoc_1::Part1, Some("74394")),
+        Entry::new(1, 2, aoc_1::Part2, Some("212836")),
+        Entry::new(2, 1, aoc_2::Part1, Some("13565")),
+        Entry::new(2, 2, aoc_2::Part2, Some("12424")),
+        Entry::new(3, 1, aoc_3::Part1, Some("7967")),
+        Entry::new(3, 2, aoc_3::Part2, Some("2716")),
+        Entry::new(4, 1, aoc_4::Part1, Some("540")),
+        Entry::new(4, 2, aoc_4::Part2, Some("872")),
+        Entry::new(5, 1, aoc_5::Part1, Some("QGTHFZBHV")),
+        Entry::new(5, 2, aoc_5::Part2, Some("MGDMPSZTM")),
+        Entry::new(6, 1, aoc_6::Part1, Some("1658")),
+        Entry::new(6, 2, aoc_6::Part2, Some("2260")),
+        Entry::new(7, 1, aoc_7::Part1, Some("919137")),
+        Entry::new(7, 2, aoc_7::Part2, Some("2877389")),
+        Entry::new(8, 1, aoc_8::Part1, Some("1690")),
+        Entry::new(8, 2, aoc_8::Part2, Some("535680")),
+        Entry::new(9, 1, aoc_9::Part1, Some("6311")),
+        Entry::new(9, 2, aoc_9::Part2, Some("2482")),
+        Entry::new(10, 1, aoc_10::Part1, Some("16880")),
+        Entry::new(10, 2, aoc_10::Part2, Some(AOC_10_2_SCREEN)),
+        Entry::new(11, 1, aoc_11::Part1, Some("108240")),
+        Entry::new(11, 2, aoc_11::Part2, Some("25712998901")),
+        Entry::new(12, 1, aoc_12::Part1, Some("425")),
+        Entry::new(12, 2, aoc_12::Part2, Some("418")),
+        Entry::new(13, 1, aoc_13::Part1, Some("6187")),
+        Entry::new(13, 2, aoc_13::Part2, Some("23520")),
+        Entry::new(14, 1, aoc_14::Part1, Some("1133")),
+        Entry::new(14, 2, aoc_14::Part2, Some("27566")),
+        Entry::new(15, 1, aoc_15::Part1, Some("5147333")),
+        Entry::new(15, 2, aoc_15::Part2, Some("13734006908372")),
+    ]
+}
@@ -0,0 +1,210 @@
+use std::{
+    cmp::{Ordering, Reverse},
+    collections::BinaryHeap,
+    iter::Sum,
+};
+
+/// Keeps the `capacity` largest keys seen so far, each with an optional payload
+/// (e.g. the index of the elf carrying the calories).
+/// The smallest kept key sits on top of a min-heap so an insertion is O(log K).
+pub struct TopK<K: Ord, P = ()> {
+    capacity: usize,
+    heap: BinaryHeap<Reverse<Entry<K, P>>>,
+}
+
+/// Ordered by key only, so payloads do not need to be comparable
+struct Entry<K: Ord, P> {
+    key: K,
+    payload: P,
+}
+
+impl<K: Ord, P> PartialEq for Entry<K, P> {
+    fn eq(&self, other: &Self) -> bool {
+        self.key == other.key
+    }
+}
+
+impl<K: Ord, P> Eq for Entry<K, P> {}
+
+impl<K: Ord, P> PartialOrd for Entry<K, P> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<K: Ord, P> Ord for Entry<K, P> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key.cmp(&other.key)
+    }
+}
+
+impl<K: Ord> TopK<K> {
+    pub fn add(&mut self, key: K) {
+        self.add_with(key, ())
+    }
+}
+
+impl<K: Ord, P> TopK<K, P> {
+    pub fn new(capacity: usize) -> Self {
+        TopK {
+            capacity,
+            heap: BinaryHeap::with_capacity(capacity + 1),
+        }
+    }
+
+    /// On a tie with the smallest kept key, the entry already kept wins
+    pub fn add_with(&mut self, key: K, payload: P) {
+        if self.heap.len() < self.capacity {
+            self.heap.push(Reverse(Entry { key, payload }));
+        } else if let Some(mut smallest) = self.heap.peek_mut() {
+            if key > smallest.0.key {
+                *smallest = Reverse(Entry { key, payload });
+            }
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    /// Smallest key kept, the one the next insertion has to beat once full
+    pub fn min(&self) -> Option<&K> {
+        self.heap.peek().map(|e| &e.0.key)
+    }
+
+    pub fn max(&self) -> Option<&K> {
+        self.heap.iter().map(|e| &e.0.key).max()
+    }
+
+    fn sorted(&self) -> Vec<(&K, &P)> {
+        let mut entries: Vec<&Entry<K, P>> = self.heap.iter().map(|e| &e.0).collect();
+        entries.sort();
+
+        entries.into_iter().map(|e| (&e.key, &e.payload)).collect()
+    }
+
+    pub fn iter_ascending(&self) -> impl Iterator<Item = (&K, &P)> {
+        self.sorted().into_iter()
+    }
+
+    pub fn iter_descending(&self) -> impl Iterator<Item = (&K, &P)> {
+        self.sorted().into_iter().rev()
+    }
+
+    /// Largest first, consuming the collection
+    pub fn into_sorted_vec(self) -> Vec<(K, P)> {
+        self.heap
+            .into_sorted_vec() //sorted on Reverse so largest key first
+            .into_iter()
+            .map(|Reverse(e)| (e.key, e.payload))
+            .collect()
+    }
+
+    /// Keeps the top `self.capacity()` of both collections
+    pub fn merge(mut self, other: TopK<K, P>) -> Self {
+        for Reverse(e) in other.heap {
+            self.add_with(e.key, e.payload);
+        }
+        self
+    }
+}
+
+impl<K: Ord + Copy + Sum, P> TopK<K, P> {
+    pub fn sum(&self) -> K {
+        self.heap.iter().map(|e| e.0.key).sum()
+    }
+}
+
+impl<K: Ord> Extend<K> for TopK<K> {
+    fn extend<T: IntoIterator<Item = K>>(&mut self, iter: T) {
+        for key in iter {
+            self.add(key);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_top_3() {
+        let mut list = TopK::new(3);
+
+        assert_eq!(0, list.sum());
+        assert_eq!(None, list.min());
+
+        list.add(3);
+        list.add(2);
+        list.add(1);
+
+        assert_eq!(6, list.sum());
+        assert_eq!(Some(&1), list.min());
+
+        list.add(4);
+        assert_eq!(3, list.len());
+        assert_eq!(9, list.sum());
+        assert_eq!(Some(&2), list.min());
+        assert_eq!(Some(&4), list.max());
+
+        list.add(0);
+        assert_eq!(9, list.sum());
+    }
+
+    #[test]
+    fn test_iterate() {
+        let mut list = TopK::new(4);
+        list.extend(vec![5, 1, 9, 3, 7, 2]);
+
+        itertools::assert_equal(list.iter_ascending().map(|(k, _)| *k), vec![3, 5, 7, 9]);
+        itertools::assert_equal(list.iter_descending().map(|(k, _)| *k), vec![9, 7, 5, 3]);
+        itertools::assert_equal(
+            list.into_sorted_vec().into_iter().map(|(k, _)| k),
+            vec![9, 7, 5, 3],
+        );
+    }
+
+    #[test]
+    fn test_payload() {
+        let mut elves = TopK::new(2);
+        elves.add_with(6000, 0);
+        elves.add_with(4000, 1);
+        elves.add_with(11000, 2);
+        elves.add_with(4000, 3);
+
+        itertools::assert_equal(
+            elves.iter_descending().map(|(k, p)| (*k, *p)),
+            vec![(11000, 2), (6000, 0)],
+        );
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut first = TopK::new(3);
+        first.extend(vec![1, 8, 4]);
+        let mut second = TopK::new(5);
+        second.extend(vec![7, 2, 9]);
+
+        let merged = first.merge(second);
+
+        assert_eq!(3, merged.capacity());
+        itertools::assert_equal(merged.iter_descending().map(|(k, _)| *k), vec![9, 8, 7]);
+    }
+
+    #[test]
+    fn test_zero_capacity() {
+        let mut list = TopK::new(0);
+        list.add(1);
+
+        assert!(list.is_empty());
+        assert_eq!(0, list.sum());
+    }
+}